@@ -0,0 +1,11 @@
+#![no_main]
+
+// each input byte picks a move in a playout from the start position; cargo fuzz
+// builds the engine with --cfg fuzzing, which exposes its invariants module
+use bitboard_generator::invariants;
+
+libfuzzer_sys::fuzz_target!(|choices: &[u8]| {
+    if let Err(err) = invariants::check_playout(choices) {
+        panic!("{}", err);
+    }
+});
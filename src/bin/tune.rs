@@ -0,0 +1,35 @@
+// Texel tuning of the handcrafted evaluation, see tune.rs:
+// tune <labelled fens> [epochs] [output]
+use bitboard_generator::tune;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(path) = args.get(1) else {
+        eprintln!("usage: tune <labelled fens> [epochs] [output]");
+        return;
+    };
+    let epochs = args.get(2).and_then(|e| e.parse().ok()).unwrap_or(1000);
+    let output = args.get(3).map_or("weights.rs", |o| o.as_str());
+    let entries = match tune::load(path) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!("{}: {}", path, error);
+            return;
+        }
+    };
+    let mut params = tune::initial_params();
+    let k = tune::fit_k(&entries, &params);
+    println!(
+        "{} positions, K {:.4}, error {:.6}",
+        entries.len(),
+        k,
+        tune::mean_squared_error(&entries, &params, k)
+    );
+    tune::tune(&entries, &mut params, k, epochs, |epoch, error| {
+        if epoch % 50 == 0 || epoch == epochs {
+            println!("epoch {} error {:.6}", epoch, error);
+        }
+    });
+    std::fs::write(output, tune::write_source(&params)).expect("could not write weights");
+    println!("wrote {}", output);
+}
@@ -0,0 +1,174 @@
+//! The binary's subcommands; `uci`, or no argument, runs the UCI loop.
+
+#[cfg(feature = "nnue")]
+use crate::nnue;
+#[cfg(feature = "reference")]
+use crate::reference;
+use crate::search;
+use crate::uci;
+use crate::{datagen, evaluation, fen, perft, perft_debug, Position};
+
+pub fn run(args: Vec<String>) {
+    #[cfg(feature = "nnue")]
+    {
+        let path = std::env::var("EVAL_FILE").unwrap_or_else(|_| "network.nnue".to_owned());
+        match nnue::Network::load(&path) {
+            Ok(network) => {
+                nnue::set_network(network);
+            }
+            Err(err) => eprintln!("could not load {}: {}, using handcrafted eval", path, err),
+        }
+    }
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("uci") | None => uci::run(),
+        Some("bench") => {
+            let depth = args.get(2).and_then(|d| d.parse().ok()).unwrap_or(5);
+            let results = search::bench(depth);
+            for result in &results {
+                if let Some(eval) = &result.evaluation {
+                    println!(
+                        "{} bestmove {} score cp {}",
+                        result.fen,
+                        eval.best_move.display(),
+                        eval.score
+                    );
+                }
+                println!(
+                    "  selective: {:>10}  full width: {:>10}  unordered: {:>10}",
+                    result.nodes.selective, result.nodes.full_width, result.nodes.unordered
+                );
+            }
+            let total = search::BenchNodes::total(&results);
+            println!(
+                "\nTotal selective: {} full width: {} unordered: {}",
+                total.selective, total.full_width, total.unordered
+            );
+        }
+        Some("search") => {
+            let depth = args.get(2).and_then(|d| d.parse().ok()).unwrap_or(6);
+            let game = match args.get(3) {
+                Some(fen) => Position::new(fen.to_owned()),
+                None => Position::default(),
+            };
+            let mut searcher = search::Searcher::new();
+            searcher.print_info = true;
+            uci::print_bestmove(searcher.iterate(&game, depth), false);
+        }
+        Some("datagen") => {
+            let Some(output) = args.get(2) else {
+                eprintln!(
+                    "usage: datagen <output> [--games n] [--threads n] [--depth n] [--nodes n] \
+                     [--random-plies n] [--max-plies n] [--seed n]"
+                );
+                return;
+            };
+            let mut config = datagen::DatagenConfig::default();
+            for option in args[3..].chunks(2) {
+                let value = option.get(1).and_then(|v| v.parse().ok());
+                match (option[0].as_str(), value) {
+                    ("--games", Some(v)) => config.games = v as usize,
+                    ("--threads", Some(v)) => config.threads = v as usize,
+                    ("--depth", Some(v)) => config.depth = v as usize,
+                    ("--nodes", Some(0)) => config.nodes = None,
+                    ("--nodes", Some(v)) => config.nodes = Some(v),
+                    ("--random-plies", Some(v)) => config.random_plies = v as usize,
+                    ("--max-plies", Some(v)) => config.max_plies = v as usize,
+                    ("--seed", Some(v)) => config.seed = v,
+                    _ => eprintln!("ignoring {}", option.join(" ")),
+                }
+            }
+            let positions = datagen::run_to_file(config, output).expect("could not write data");
+            println!("{} positions from {} games", positions, config.games);
+        }
+        Some("eval") => {
+            let game = match args.get(2) {
+                Some(fen) => Position::new(fen.to_owned()),
+                None => Position::default(),
+            };
+            println!("{}", evaluation::trace(&game));
+            println!("side to move: {}", game.static_eval());
+        }
+        Some("perft") => {
+            let depth = args.get(2).and_then(|d| d.parse().ok()).unwrap_or(5);
+            let game = match args.get(3) {
+                Some(fen) if !fen.starts_with("--") => Position::new(fen.to_owned()),
+                _ => Position::default(),
+            };
+            let (mut threads, mut hash_mb) = (1, 0);
+            let options_at = args.iter().position(|arg| arg.starts_with("--"));
+            for option in args[options_at.unwrap_or(args.len())..].chunks(2) {
+                let value = option.get(1).and_then(|v| v.parse().ok());
+                match (option[0].as_str(), value) {
+                    ("--threads", Some(v)) => threads = v,
+                    ("--hash", Some(v)) => hash_mb = v,
+                    _ => eprintln!("ignoring {}", option.join(" ")),
+                }
+            }
+            let table = (hash_mb > 0).then(|| perft::PerftTable::new(hash_mb));
+            let start = std::time::Instant::now();
+            let counts = perft::divide(&game, depth, threads, table.as_ref());
+            for (piece_move, nodes) in &counts {
+                println!("{}: {}", piece_move.display(), nodes);
+            }
+            let nodes: u64 = counts.iter().map(|(_, nodes)| nodes).sum();
+            println!("\nNodes searched: {}", nodes);
+            eprintln!("{} ms", start.elapsed().as_millis());
+        }
+        Some("perft-bench") => {
+            let depth = args.get(2).and_then(|d| d.parse().ok()).unwrap_or(4);
+            let results = perft::bench(depth);
+            for &(fen, nodes, elapsed) in &results {
+                let nps = perft::nodes_per_second(nodes, elapsed);
+                println!("{} {:>10} nodes {:>10} nps", fen, nodes, nps);
+            }
+            let nodes = results.iter().map(|&(_, nodes, _)| nodes).sum();
+            let elapsed = results.iter().map(|&(_, _, elapsed)| elapsed).sum();
+            println!(
+                "total {} nodes {} nps",
+                nodes,
+                perft::nodes_per_second(nodes, elapsed)
+            );
+        }
+        Some("perft-debug") => {
+            let (Some(engine), Some(depth)) =
+                (args.get(2), args.get(3).and_then(|d| d.parse().ok()))
+            else {
+                eprintln!("usage: perft-debug <reference engine> <depth> [fen]");
+                return;
+            };
+            let fen = args.get(4).map_or(fen::START_FEN, |fen| fen.as_str());
+            if let Err(err) = fen.parse::<Position>() {
+                eprintln!("invalid fen: {}", err);
+                return;
+            }
+            let mut reference = match perft_debug::UciEngine::spawn(engine) {
+                Ok(reference) => reference,
+                Err(err) => {
+                    eprintln!("could not start {}: {}", engine, err);
+                    return;
+                }
+            };
+            let on_step = |text: &str, ours: u64, theirs: u64| {
+                eprintln!("{}: {} here, {} for the reference", text, ours, theirs)
+            };
+            match perft_debug::find_discrepancy(&mut reference, fen, depth, on_step) {
+                Ok(Some(discrepancy)) => println!("{}", discrepancy),
+                Ok(None) => println!("all counts match"),
+                Err(err) => eprintln!("reference engine failed: {}", err),
+            }
+        }
+        #[cfg(feature = "reference")]
+        Some("reference-check") => {
+            let positions = args
+                .get(2)
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(1_000_000);
+            let seed = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(0);
+            match reference::differential(positions, seed) {
+                Ok(checked) => println!("{} positions, all move lists match", checked),
+                Err(err) => println!("{}", err),
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::move_ordering::is_capture;
+use crate::search::{is_mate_score, Searcher};
+use crate::util::Bitboard;
+use crate::zobrist::splitmix64;
+use crate::{Color, PieceType, Position};
+
+// a side this far ahead for ADJUDICATION_PLIES in a row is scored as the winner
+const ADJUDICATION_SCORE: i32 = 1500;
+const ADJUDICATION_PLIES: usize = 8;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DatagenConfig {
+    pub games: usize,
+    pub threads: usize,
+    pub depth: usize,
+    pub nodes: Option<u64>,
+    pub random_plies: usize,
+    pub max_plies: usize, // drawn when reached
+    pub seed: u64,
+}
+
+impl Default for DatagenConfig {
+    fn default() -> DatagenConfig {
+        DatagenConfig {
+            games: 100,
+            threads: 1,
+            depth: 8,
+            nodes: Some(5000),
+            random_plies: 8,
+            max_plies: 400,
+            seed: 0,
+        }
+    }
+}
+
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        let (state, value) = splitmix64(self.0);
+        self.0 = state;
+        (value % n as u64) as usize
+    }
+}
+
+// nothing but kings, or a lone minor piece against a bare king
+fn insufficient_material(state: &Position) -> bool {
+    let pieces = &state.pieces;
+    let [white, black] = [Color::White, Color::Black].map(|color| pieces.bitboards(color));
+    let heavy = |bb: [Bitboard; 6]| {
+        bb[PieceType::Queen as usize] | bb[PieceType::Rook as usize] | bb[PieceType::Pawn as usize]
+    };
+    let minors = |bb: [Bitboard; 6]| {
+        (bb[PieceType::Bishop as usize] | bb[PieceType::Knight as usize]).popcount()
+    };
+    (heavy(white) | heavy(black)).is_empty() && minors(white) + minors(black) <= 1
+}
+
+// random legal moves from the start position; None if the game ended on the way
+fn random_opening(rng: &mut Rng, plies: usize) -> Option<Position> {
+    let mut state = Position::default();
+    for _ in 0..plies {
+        let moves = state.moves(state.active_color);
+        if moves.is_empty() {
+            return None;
+        }
+        state = state.apply_move(moves[rng.below(moves.len())]);
+    }
+    (!state.moves(state.active_color).is_empty()).then_some(state)
+}
+
+// plays one game and returns "<fen> | <score> | <result>" lines, score and result
+// from White's point of view
+pub fn play_game(config: &DatagenConfig, game: usize) -> Vec<String> {
+    let mut rng = Rng(splitmix64(config.seed ^ game as u64).1);
+    let mut state = loop {
+        if let Some(state) = random_opening(&mut rng, config.random_plies) {
+            break state;
+        }
+    };
+    let mut searcher = Searcher::new();
+    searcher.node_limit = config.nodes;
+
+    let mut positions: Vec<(String, i32)> = vec![];
+    // hashes since the last capture or pawn move, for repetitions and the 50 move rule
+    let mut history = vec![state.hash];
+    let mut winning_plies = 0;
+    let mut result = 0.5;
+    for _ in 0..config.max_plies {
+        if state.moves(state.active_color).is_empty() {
+            if state.in_check() {
+                result = if state.active_color == Color::White {
+                    0.0
+                } else {
+                    1.0
+                };
+            }
+            break;
+        }
+        if insufficient_material(&state) || history.len() > 100 {
+            break;
+        }
+        if history[..history.len() - 1]
+            .iter()
+            .filter(|&&hash| hash == state.hash)
+            .count()
+            >= 2
+        {
+            break;
+        }
+        let Some(eval) = searcher.iterate(&state, config.depth) else {
+            break;
+        };
+        let score = match state.active_color {
+            Color::White => eval.score,
+            Color::Black => -eval.score,
+        };
+        if score.abs() >= ADJUDICATION_SCORE {
+            winning_plies += 1;
+            if winning_plies >= ADJUDICATION_PLIES {
+                result = if score > 0 { 1.0 } else { 0.0 };
+                break;
+            }
+        } else {
+            winning_plies = 0;
+        }
+        // only quiet positions: the score of a capture sequence isn't the eval's job
+        let quiet = !state.in_check()
+            && !is_capture(&state, eval.best_move)
+            && eval.best_move.promoted_piece.is_none()
+            && !is_mate_score(eval.score);
+        if quiet {
+            positions.push((state.fen(), score));
+        }
+        let reversible = !is_capture(&state, eval.best_move)
+            && state
+                .pieces
+                .piece_on(eval.best_move.from)
+                .map(|piece| piece.piece_type)
+                != Some(PieceType::Pawn);
+        state = state.apply_move(eval.best_move);
+        if !reversible {
+            history.clear();
+        }
+        history.push(state.hash);
+    }
+    positions
+        .into_iter()
+        .map(|(fen, score)| format!("{} | {} | {:.1}", fen, score, result))
+        .collect()
+}
+
+// games are spread over the threads but seeded and written by index, so the output
+// only depends on the config
+pub fn run(config: DatagenConfig, output: &mut impl Write) -> io::Result<usize> {
+    let (sender, receiver) = mpsc::channel();
+    let threads = config.threads.max(1);
+    let handles: Vec<_> = (0..threads)
+        .map(|thread| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for game in (thread..config.games).step_by(threads) {
+                    if sender.send((game, play_game(&config, game))).is_err() {
+                        break;
+                    }
+                }
+            })
+        })
+        .collect();
+    drop(sender);
+
+    let mut pending = BTreeMap::new();
+    let mut next = 0;
+    let mut written = 0;
+    for (game, lines) in receiver {
+        pending.insert(game, lines);
+        while let Some(lines) = pending.remove(&next) {
+            for line in &lines {
+                writeln!(output, "{}", line)?;
+            }
+            written += lines.len();
+            next += 1;
+        }
+    }
+    for handle in handles {
+        handle.join().expect("datagen thread panicked");
+    }
+    output.flush()?;
+    Ok(written)
+}
+
+pub fn run_to_file(config: DatagenConfig, path: &str) -> io::Result<usize> {
+    run(config, &mut BufWriter::new(File::create(path)?))
+}
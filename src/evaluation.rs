@@ -0,0 +1,456 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::pawns::{self, pawn_attacks, relative_rank, PawnEval, PawnTable, PAWN_TERMS};
+use crate::position::Pieces;
+use crate::sliding_pieces::{
+    east_attacks, noea_attacks, nort_attacks, nowe_attacks, soea_attacks, sout_attacks,
+    sowe_attacks, west_attacks,
+};
+use crate::types::Invert;
+use crate::util::{Bitboard, Square};
+use crate::weights::WEIGHTS;
+use crate::{Color, PieceType, Position};
+
+const PAWN_TABLE_ENTRIES: usize = 1 << 14;
+
+// (midgame, endgame) pair, interpolated by game phase at the end of the evaluation
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Score(pub i32, pub i32);
+
+impl Add for Score {
+    type Output = Score;
+    fn add(self, other: Score) -> Score {
+        Score(self.0 + other.0, self.1 + other.1)
+    }
+}
+impl Sub for Score {
+    type Output = Score;
+    fn sub(self, other: Score) -> Score {
+        Score(self.0 - other.0, self.1 - other.1)
+    }
+}
+impl Mul<i32> for Score {
+    type Output = Score;
+    fn mul(self, factor: i32) -> Score {
+        Score(self.0 * factor, self.1 * factor)
+    }
+}
+impl Neg for Score {
+    type Output = Score;
+    fn neg(self) -> Score {
+        Score(-self.0, -self.1)
+    }
+}
+impl AddAssign for Score {
+    fn add_assign(&mut self, other: Score) {
+        *self = *self + other;
+    }
+}
+impl SubAssign for Score {
+    fn sub_assign(&mut self, other: Score) {
+        *self = *self - other;
+    }
+}
+
+const PHASE_WEIGHTS: [i32; 6] = [0, 4, 2, 1, 1, 0];
+pub const MAX_PHASE: i32 = 24;
+
+// offsets into the flat parameter vector `WEIGHTS`, which the tuner rewrites
+pub const MATERIAL: usize = 0; // by PieceType
+pub const PST: usize = MATERIAL + 6; // [PieceType][square], White's view with a8 first
+pub const PASSED: usize = PST + 6 * 64; // by rank from the pawn's own side
+pub const CONNECTED: usize = PASSED + 8;
+pub const ISOLATED: usize = CONNECTED + 8;
+pub const DOUBLED: usize = ISOLATED + 1;
+pub const BACKWARD: usize = DOUBLED + 1;
+pub const MOBILITY: usize = BACKWARD + 1; // by PieceType, per square above the base count
+pub const SHIELD: usize = MOBILITY + 6; // by ranks between the king and its nearest pawn
+pub const STORM: usize = SHIELD + 4;
+pub const BISHOP_PAIR: usize = STORM + 5;
+pub const SPACE: usize = BISHOP_PAIR + 1;
+pub const PARAM_COUNT: usize = SPACE + 1;
+
+pub const SECTIONS: [(&str, usize); 17] = [
+    ("material", MATERIAL),
+    ("king piece-square", PST),
+    ("queen piece-square", PST + 64),
+    ("rook piece-square", PST + 2 * 64),
+    ("bishop piece-square", PST + 3 * 64),
+    ("knight piece-square", PST + 4 * 64),
+    ("pawn piece-square", PST + 5 * 64),
+    ("passed", PASSED),
+    ("connected", CONNECTED),
+    ("isolated", ISOLATED),
+    ("doubled", DOUBLED),
+    ("backward", BACKWARD),
+    ("mobility", MOBILITY),
+    ("pawn shield", SHIELD),
+    ("pawn storm", STORM),
+    ("bishop pair", BISHOP_PAIR),
+    ("space", SPACE),
+];
+
+const MOBILITY_BASE: [i32; 6] = [0, 13, 7, 6, 4, 0];
+// attack units per king-zone square hit by the piece
+const KING_ATTACK_UNITS: [i32; 6] = [0, 5, 3, 2, 2, 0];
+const MAX_KING_DANGER: i32 = 500;
+// files c-f, ranks 2-4 from each side's point of view
+const WHITE_SPACE_AREA: Bitboard = Bitboard(0x3c3c3c00);
+const BLACK_SPACE_AREA: Bitboard = Bitboard(0x3c3c3c00000000);
+const FILE_A: Bitboard = Bitboard(0x0101010101010101);
+
+// where terms get their parameters from: the evaluation reads `WEIGHTS`, the tuner
+// records how often each one is used instead
+pub trait Weights {
+    fn weight(&mut self, color: Color, param: usize, count: i32) -> Score;
+}
+
+pub struct StaticWeights;
+
+impl Weights for StaticWeights {
+    fn weight(&mut self, _: Color, param: usize, count: i32) -> Score {
+        WEIGHTS[param] * count
+    }
+}
+
+// 24 with all minor and major pieces on the board, 0 with only kings and pawns
+pub fn phase(pieces: &Pieces) -> i32 {
+    let mut phase = 0;
+    for color in [Color::White, Color::Black] {
+        for (piece, bb) in pieces.bitboards(color).into_iter().enumerate() {
+            phase += PHASE_WEIGHTS[piece] * bb.popcount() as i32;
+        }
+    }
+    phase.min(MAX_PHASE)
+}
+
+pub fn taper(score: Score, phase: i32) -> i32 {
+    (score.0 * phase + score.1 * (MAX_PHASE - phase)) / MAX_PHASE
+}
+
+fn material(pieces: &Pieces, color: Color, weights: &mut impl Weights) -> Score {
+    let mut score = Score::default();
+    for (piece, bb) in pieces.bitboards(color).into_iter().enumerate() {
+        score += weights.weight(color, MATERIAL + piece, bb.popcount() as i32);
+    }
+    score
+}
+
+fn piece_square(pieces: &Pieces, color: Color, weights: &mut impl Weights) -> Score {
+    let mut score = Score::default();
+    for (piece, bb) in pieces.bitboards(color).into_iter().enumerate() {
+        for square in bb {
+            let index = match color {
+                Color::White => square.flip(),
+                Color::Black => square,
+            };
+            score += weights.weight(color, PST + piece * 64 + index.index(), 1);
+        }
+    }
+    score
+}
+
+fn pieces_of(pieces: &Pieces, color: Color) -> Bitboard {
+    pieces.occupancy(color)
+}
+
+fn king_square(state: &Position, color: Color) -> Square {
+    state.king_square[color as usize]
+}
+
+// squares attacked by a single knight, bishop, rook or queen
+fn piece_attacks(state: &Position, piece: usize, square: Square) -> Bitboard {
+    let bb = square.bitboard();
+    let empty = state.empty;
+    let straight = || {
+        nort_attacks(bb, empty)
+            | east_attacks(bb, empty)
+            | sout_attacks(bb, empty)
+            | west_attacks(bb, empty)
+    };
+    let diagonal = || {
+        noea_attacks(bb, empty)
+            | soea_attacks(bb, empty)
+            | sowe_attacks(bb, empty)
+            | nowe_attacks(bb, empty)
+    };
+    match piece {
+        p if p == PieceType::Queen as usize => straight() | diagonal(),
+        p if p == PieceType::Rook as usize => straight(),
+        p if p == PieceType::Bishop as usize => diagonal(),
+        p if p == PieceType::Knight as usize => state.knight_lookup[square],
+        _ => Bitboard::EMPTY,
+    }
+}
+
+// (mobility, king attack) of `color`'s pieces; squares occupied by own pieces or
+// covered by enemy pawns don't count as mobility
+fn mobility_and_attack(
+    state: &Position,
+    color: Color,
+    weights: &mut impl Weights,
+) -> (Score, Score) {
+    let pieces = &state.pieces;
+    let enemy = color.invert();
+    let enemy_pawns = pieces.bitboards(enemy)[PieceType::Pawn as usize];
+    let area = !pieces_of(pieces, color) & !pawn_attacks(enemy_pawns, enemy);
+    let enemy_king = king_square(state, enemy);
+    let king_zone = state.king_lookup[enemy_king] | enemy_king.bitboard();
+
+    let mut mobility = Score::default();
+    let mut attackers = 0;
+    let mut units = 0;
+    for (piece, bb) in pieces.bitboards(color).into_iter().enumerate() {
+        for square in bb {
+            let attacks = piece_attacks(state, piece, square);
+            if !attacks.is_empty() {
+                let count = (attacks & area).popcount() as i32;
+                mobility += weights.weight(color, MOBILITY + piece, count - MOBILITY_BASE[piece]);
+                if !(attacks & king_zone).is_empty() {
+                    attackers += 1;
+                    units += KING_ATTACK_UNITS[piece] * (attacks & king_zone).popcount() as i32;
+                }
+            }
+        }
+    }
+    // a lone attacker is rarely dangerous
+    if attackers < 2 {
+        return (mobility, Score::default());
+    }
+    // every king-zone square the enemy king can't step to adds a unit
+    let danger = state.attacks(color, state.empty | enemy_king.bitboard());
+    units += (king_zone & danger).popcount() as i32;
+    let attack = Score((units * units / 8).min(MAX_KING_DANGER), units);
+    (mobility, attack)
+}
+
+// nearest pawn of `pawns` on the king's file and its neighbours, by ranks in front of the king
+fn pawn_distances(
+    pawns: Bitboard,
+    king: Square,
+    color: Color,
+) -> impl Iterator<Item = Option<usize>> {
+    let king_file = king.file();
+    let king_rank = relative_rank(king, color);
+    (king_file.saturating_sub(1)..=(king_file + 1).min(7)).map(move |file| {
+        let mut nearest = None;
+        for pawn in pawns & FILE_A << file as u32 {
+            let rank = relative_rank(pawn, color);
+            if rank > king_rank {
+                let distance = rank - king_rank;
+                nearest = Some(nearest.map_or(distance, |n: usize| n.min(distance)));
+            }
+        }
+        nearest
+    })
+}
+
+fn pawn_shield(state: &Position, color: Color, weights: &mut impl Weights) -> Score {
+    let pawns = state.pieces.bitboards(color)[PieceType::Pawn as usize];
+    let mut score = Score::default();
+    for distance in pawn_distances(pawns, king_square(state, color), color) {
+        let index = distance.map_or(0, |d| d.min(STORM - SHIELD - 1));
+        score += weights.weight(color, SHIELD + index, 1);
+    }
+    score
+}
+
+fn pawn_storm(state: &Position, color: Color, weights: &mut impl Weights) -> Score {
+    let pawns = state.pieces.bitboards(color.invert())[PieceType::Pawn as usize];
+    let mut score = Score::default();
+    for distance in pawn_distances(pawns, king_square(state, color), color).flatten() {
+        if distance < BISHOP_PAIR - STORM {
+            score += weights.weight(color, STORM + distance, 1);
+        }
+    }
+    score
+}
+
+fn bishop_pair(pieces: &Pieces, color: Color, weights: &mut impl Weights) -> Score {
+    match pieces.bitboards(color)[PieceType::Bishop as usize].popcount() {
+        2.. => weights.weight(color, BISHOP_PAIR, 1),
+        _ => Score::default(),
+    }
+}
+
+// safe squares controlled behind and around the own centre pawns
+fn space(state: &Position, color: Color, weights: &mut impl Weights) -> Score {
+    let pieces = &state.pieces;
+    let enemy = color.invert();
+    let enemy_pawns = pieces.bitboards(enemy)[PieceType::Pawn as usize];
+    let controlled = state.attacks(color, state.empty);
+    let area = match color {
+        Color::White => WHITE_SPACE_AREA,
+        Color::Black => BLACK_SPACE_AREA,
+    };
+    let safe = controlled & area & !pawn_attacks(enemy_pawns, enemy);
+    weights.weight(color, SPACE, safe.popcount() as i32)
+}
+
+// hands every named term to `visit` as (name, white, black)
+fn visit_terms(
+    state: &Position,
+    pawn_eval: &PawnEval,
+    weights: &mut impl Weights,
+    mut visit: impl FnMut(&'static str, Score, Score),
+) {
+    let pieces = &state.pieces;
+    let white = material(pieces, Color::White, weights);
+    visit("material", white, material(pieces, Color::Black, weights));
+    let white = piece_square(pieces, Color::White, weights);
+    visit(
+        "piece-square",
+        white,
+        piece_square(pieces, Color::Black, weights),
+    );
+    for (i, name) in PAWN_TERMS.into_iter().enumerate() {
+        visit(name, pawn_eval.white[i], pawn_eval.black[i]);
+    }
+    let (white_mobility, white_attack) = mobility_and_attack(state, Color::White, weights);
+    let (black_mobility, black_attack) = mobility_and_attack(state, Color::Black, weights);
+    visit("mobility", white_mobility, black_mobility);
+    // an attack on the enemy king is scored for the attacker
+    visit("king attack", white_attack, black_attack);
+    let white = pawn_shield(state, Color::White, weights);
+    visit(
+        "pawn shield",
+        white,
+        pawn_shield(state, Color::Black, weights),
+    );
+    let white = pawn_storm(state, Color::White, weights);
+    visit(
+        "pawn storm",
+        white,
+        pawn_storm(state, Color::Black, weights),
+    );
+    let white = bishop_pair(pieces, Color::White, weights);
+    visit(
+        "bishop pair",
+        white,
+        bishop_pair(pieces, Color::Black, weights),
+    );
+    let white = space(state, Color::White, weights);
+    visit("space", white, space(state, Color::Black, weights));
+}
+
+fn evaluate_with(state: &Position, pawn_eval: &PawnEval) -> i32 {
+    let mut total = Score::default();
+    visit_terms(state, pawn_eval, &mut StaticWeights, |_, white, black| {
+        total += white - black
+    });
+    let score = taper(total, phase(&state.pieces));
+    match state.active_color {
+        Color::White => score,
+        Color::Black => -score,
+    }
+}
+
+// runs every term against `weights`; returns the white-minus-black total and the phase
+pub fn evaluate_weighted(state: &Position, weights: &mut impl Weights) -> (Score, i32) {
+    let pawn_eval = pawns::evaluate_with(&state.pieces, weights);
+    let mut total = Score::default();
+    visit_terms(state, &pawn_eval, weights, |_, white, black| {
+        total += white - black
+    });
+    (total, phase(&state.pieces))
+}
+
+#[cfg(feature = "nnue")]
+fn nnue_evaluate(state: &Position) -> Option<i32> {
+    let network = crate::nnue::network()?;
+    let accumulator = state.accumulator.as_ref()?;
+    Some(crate::nnue::evaluate(
+        network,
+        accumulator,
+        state.active_color,
+    ))
+}
+
+// centipawns from the side to move's point of view, without any caching
+pub fn evaluate(state: &Position) -> i32 {
+    #[cfg(feature = "nnue")]
+    if let Some(score) = nnue_evaluate(state) {
+        return score;
+    }
+    evaluate_with(state, &pawns::evaluate(&state.pieces))
+}
+
+fn trace_with(state: &Position, pawn_eval: &PawnEval) -> Trace {
+    let mut terms = vec![];
+    let mut total = Score::default();
+    visit_terms(
+        state,
+        pawn_eval,
+        &mut StaticWeights,
+        |name, white, black| {
+            total += white - black;
+            terms.push(Term { name, white, black });
+        },
+    );
+    let phase = phase(&state.pieces);
+    Trace {
+        terms,
+        phase,
+        score: taper(total, phase),
+    }
+}
+
+pub fn trace(state: &Position) -> Trace {
+    trace_with(state, &pawns::evaluate(&state.pieces))
+}
+
+// evaluation with its caches, one per searcher
+pub struct Evaluator {
+    pawn_table: PawnTable,
+}
+
+impl Evaluator {
+    pub fn new() -> Evaluator {
+        Evaluator {
+            pawn_table: PawnTable::new(PAWN_TABLE_ENTRIES),
+        }
+    }
+    pub fn evaluate(&mut self, state: &Position) -> i32 {
+        #[cfg(feature = "nnue")]
+        if let Some(score) = nnue_evaluate(state) {
+            return score;
+        }
+        let pawn_eval = self.pawn_table.get(state.pawn_hash, &state.pieces);
+        evaluate_with(state, &pawn_eval)
+    }
+}
+
+pub struct Term {
+    pub name: &'static str,
+    pub white: Score,
+    pub black: Score,
+}
+
+pub struct Trace {
+    pub terms: Vec<Term>,
+    pub phase: i32,
+    pub score: i32, // white's point of view
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<14}|{:>14} |{:>14} |{:>14}",
+            "term", "white mg  eg", "black mg  eg", "total mg  eg"
+        )?;
+        writeln!(f, "{}", "-".repeat(60))?;
+        for term in &self.terms {
+            let total = term.white - term.black;
+            writeln!(
+                f,
+                "{:<14}|{:>7} {:>6} |{:>7} {:>6} |{:>7} {:>6}",
+                term.name, term.white.0, term.white.1, term.black.0, term.black.1, total.0, total.1
+            )?;
+        }
+        writeln!(f, "{}", "-".repeat(60))?;
+        writeln!(f, "phase: {}/{}", self.phase, MAX_PHASE)?;
+        write!(f, "score: {} cp (white's point of view)", self.score)
+    }
+}
@@ -0,0 +1,300 @@
+//! Reading and writing positions in Forsyth-Edwards Notation.
+//!
+//! ```
+//! use bitboard_generator::Position;
+//!
+//! let position: Position = bitboard_generator::fen::START_FEN.parse().unwrap();
+//! assert_eq!(position.fen(), bitboard_generator::fen::START_FEN);
+//! ```
+
+use std::str::FromStr;
+
+use crate::position::{Pieces, Position};
+use crate::types::{Color, Invert, Piece, PieceType};
+use crate::util::Square;
+
+/// The standard starting position.
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// The Chess960 start position `index`, 0 to 959 in Scharnagl's numbering, where
+/// 518 is the standard start position. Castling is written KQkq, as in X-FEN.
+pub fn chess960_fen(index: usize) -> Option<String> {
+    // the knight pairs, as positions among the five squares still free
+    const KNIGHTS: [(usize, usize); 10] = [
+        (0, 1),
+        (0, 2),
+        (0, 3),
+        (0, 4),
+        (1, 2),
+        (1, 3),
+        (1, 4),
+        (2, 3),
+        (2, 4),
+        (3, 4),
+    ];
+    if index >= 960 {
+        return None;
+    }
+    let mut back_rank = [None; 8];
+    back_rank[index % 4 * 2 + 1] = Some('b');
+    back_rank[index / 4 % 4 * 2] = Some('b');
+    let free = |back_rank: &[Option<char>; 8]| -> Vec<usize> {
+        (0..8).filter(|&file| back_rank[file].is_none()).collect()
+    };
+    back_rank[free(&back_rank)[index / 16 % 6]] = Some('q');
+    let (first, second) = KNIGHTS[index / 96];
+    let squares = free(&back_rank);
+    back_rank[squares[first]] = Some('n');
+    back_rank[squares[second]] = Some('n');
+    for (file, piece) in free(&back_rank).into_iter().zip(['r', 'k', 'r']) {
+        back_rank[file] = Some(piece);
+    }
+    let black: String = back_rank.iter().flatten().collect();
+    Some(format!(
+        "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1",
+        black,
+        black.to_uppercase()
+    ))
+}
+
+// the castling rooks of a standard game, see Position::castling_rooks
+const STANDARD_CASTLING_ROOKS: [[Square; 2]; 2] = [
+    [Square::new(0, 0), Square::new(7, 0)],
+    [Square::new(0, 7), Square::new(7, 7)],
+];
+
+fn piece_type(piece: char) -> Option<PieceType> {
+    let lowercase_piece = piece.to_lowercase().to_owned().to_string();
+    match &*lowercase_piece {
+        "k" => Some(PieceType::King),
+        "q" => Some(PieceType::Queen),
+        "r" => Some(PieceType::Rook),
+        "b" => Some(PieceType::Bishop),
+        "n" => Some(PieceType::Knight),
+        "p" => Some(PieceType::Pawn),
+        _ => None,
+    }
+}
+
+fn piece_color(piece: char) -> Option<Color> {
+    if piece.is_numeric() {
+        return None;
+    }
+    if piece.is_uppercase() {
+        return Some(Color::White);
+    }
+    Some(Color::Black)
+}
+
+impl Pieces {
+    // the board field of a fen, eighth rank first
+    fn from_board(board: &str) -> Result<Pieces, String> {
+        let mut pieces = Pieces::default();
+        let ranks: Vec<&str> = board.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(format!("{} ranks instead of 8", ranks.len()));
+        }
+        for (rank_count, rank) in ranks.into_iter().enumerate() {
+            let mut file = 0;
+            for c in rank.chars() {
+                match (piece_color(c), piece_type(c), c.to_digit(10)) {
+                    (Some(color), Some(piece_type), _) if file < 8 => {
+                        let square = Square::new(file, 7 - rank_count as u8);
+                        pieces.put_piece(Piece { color, piece_type }, square);
+                        file += 1;
+                    }
+                    (_, _, Some(empty @ 1..=8)) => file += empty as u8,
+                    _ => return Err(format!("invalid rank {:?}", rank)),
+                }
+            }
+            if file != 8 {
+                return Err(format!("rank {:?} doesn't have 8 squares", rank));
+            }
+        }
+        for color in [Color::White, Color::Black] {
+            if pieces.bitboard(color, PieceType::King).popcount() != 1 {
+                return Err(format!("{:?} doesn't have exactly one king", color));
+            }
+        }
+        Ok(pieces)
+    }
+}
+
+// the rook a castling letter names and whether it is on the kingside: K and Q the
+// outermost rook on that side of the king (X-FEN), a file letter the rook on that
+// file (Shredder-FEN, and X-FEN for an inner rook); K and Q without a rook there
+// fall back to the corner
+fn castling_rook(pieces: &Pieces, color: Color, letter: char) -> Option<(Square, bool)> {
+    let back_rank = match color {
+        Color::White => 0,
+        Color::Black => 7,
+    };
+    let king = pieces.bitboard(color, PieceType::King).lsb()?;
+    let mut rooks = pieces
+        .bitboard(color, PieceType::Rook)
+        .into_iter()
+        .filter(|rook| rook.rank() == back_rank);
+    match letter {
+        'k' => {
+            let rook = rooks.filter(|rook| rook.file() > king.file()).last();
+            Some((rook.unwrap_or(Square::new(7, back_rank)), true))
+        }
+        'q' => {
+            let rook = rooks.find(|rook| rook.file() < king.file());
+            Some((rook.unwrap_or(Square::new(0, back_rank)), false))
+        }
+        'a'..='h' => {
+            let file = letter as u8 - b'a';
+            (king.rank() == back_rank && file != king.file())
+                .then_some((Square::new(file, back_rank), file > king.file()))
+        }
+        _ => None,
+    }
+}
+
+// EPD lines leave the clocks out, they default to 0 and 1
+impl FromStr for Position {
+    type Err = String;
+    fn from_str(fen: &str) -> Result<Position, String> {
+        let parsed: Vec<&str> = fen.split_whitespace().collect();
+        let [board, color, castling, en_passant, clocks @ ..] = parsed.as_slice() else {
+            return Err(format!("{:?} has fewer than 4 fields", fen));
+        };
+        let pieces = Pieces::from_board(board)?;
+        let active_color = match color.to_lowercase().as_str() {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(format!("invalid color {:?}", color)),
+        };
+        let mut castling_rights = [(false, false); 2];
+        let mut castling_rooks = STANDARD_CASTLING_ROOKS;
+        for c in castling.chars() {
+            if c == '-' && castling.len() == 1 {
+                continue;
+            }
+            let color = match c.is_ascii_uppercase() {
+                true => Color::White,
+                false => Color::Black,
+            };
+            let invalid = || format!("invalid castling rights {:?}", castling);
+            let (rook, kingside) =
+                castling_rook(&pieces, color, c.to_ascii_lowercase()).ok_or_else(invalid)?;
+            let rights = &mut castling_rights[color as usize];
+            match kingside {
+                true => rights.1 = true,
+                false => rights.0 = true,
+            }
+            castling_rooks[color as usize][kingside as usize] = rook;
+        }
+        let en_passant = match *en_passant {
+            "-" => None,
+            text => match text.parse::<Square>()? {
+                square if square.rank() == 2 || square.rank() == 5 => Some(square),
+                square => return Err(format!("en passant square {} isn't on rank 3 or 6", square)),
+            },
+        };
+        let clock = |index: usize, default: usize| match clocks.get(index) {
+            Some(text) => text
+                .parse()
+                .map_err(|_| format!("invalid move counter {:?}", text)),
+            None => Ok(default),
+        };
+        let position = Position::from_parts(
+            pieces,
+            active_color,
+            castling_rights,
+            castling_rooks,
+            en_passant,
+            clock(0, 0)?,
+            clock(1, 1)?,
+        );
+        // the king of the side not to move could be taken
+        let their_king = position.king_square[active_color.invert() as usize];
+        if position.is_square_attacked(their_king, active_color) {
+            return Err("the side not to move is in check".to_owned());
+        }
+        Ok(position)
+    }
+}
+
+impl Position {
+    // for fens known to be valid, like the engine's own and the test suites
+    pub(crate) fn new(fen: String) -> Position {
+        fen.parse().unwrap_or_else(|err| panic!("{}: {}", fen, err))
+    }
+    /// The position as a FEN string, the inverse of parsing one.
+    pub fn fen(&self) -> String {
+        let mut ranks = vec![];
+        for rank in (0..8).rev() {
+            let mut row = String::new();
+            let mut empty = 0;
+            for file in 0..8 {
+                let bit = Square::new(file, rank).bitboard();
+                let piece = [Color::White, Color::Black].into_iter().find_map(|color| {
+                    let index = self
+                        .pieces
+                        .bitboards(color)
+                        .iter()
+                        .position(|&bb| !(bb & bit).is_empty())?;
+                    let c = ['k', 'q', 'r', 'b', 'n', 'p'][index];
+                    Some(match color {
+                        Color::White => c.to_ascii_uppercase(),
+                        Color::Black => c,
+                    })
+                });
+                match piece {
+                    Some(c) => {
+                        if empty > 0 {
+                            row.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        row.push(c);
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                row.push_str(&empty.to_string());
+            }
+            ranks.push(row);
+        }
+        let ((wq, wk), (bq, bk)) = (self.white_castling, self.black_castling);
+        let castling: String = [
+            (wk, Color::White, 1),
+            (wq, Color::White, 0),
+            (bk, Color::Black, 1),
+            (bq, Color::Black, 0),
+        ]
+        .into_iter()
+        .filter(|&(allowed, _, _)| allowed)
+        .map(|(_, color, side)| self.castling_letter(color, side))
+        .collect();
+        format!(
+            "{} {} {} {} {} {}",
+            ranks.join("/"),
+            if self.active_color == Color::White {
+                "w"
+            } else {
+                "b"
+            },
+            if castling.is_empty() { "-" } else { &castling },
+            self.en_passant
+                .map_or("-".to_owned(), |square| square.to_string()),
+            self.halfmoves,
+            self.fullmoves
+        )
+    }
+    // X-FEN: K or Q unless another rook stands further out on that side, which
+    // only happens in Chess960
+    fn castling_letter(&self, color: Color, side: usize) -> char {
+        let rook = self.castling_rooks[color as usize][side];
+        let letter = match castling_rook(&self.pieces, color, ['q', 'k'][side]) {
+            Some((outermost, _)) if outermost == rook => ['Q', 'K'][side],
+            _ => (b'A' + rook.file()) as char,
+        };
+        match color {
+            Color::White => letter,
+            Color::Black => letter.to_ascii_lowercase(),
+        }
+    }
+}
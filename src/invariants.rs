@@ -0,0 +1,105 @@
+use crate::reference::{self, king_square};
+use crate::util::{Bitboard, Square};
+use crate::{Color, Piece, PieceType, Position};
+
+// movegen invariants checked along random playouts, shared by the proptest suite
+// and the fuzz target in fuzz/
+
+const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::King,
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+fn check_pieces(state: &Position) -> Result<(), String> {
+    let pieces = &state.pieces;
+    for color in [Color::White, Color::Black] {
+        let bitboards = pieces.bitboards(color);
+        let union = bitboards
+            .iter()
+            .fold(Bitboard::EMPTY, |union, &bb| union | bb);
+        if union != pieces.occupancy(color)
+            || bitboards.iter().map(|bb| bb.popcount()).sum::<u32>() != union.popcount()
+        {
+            return Err(format!(
+                "{:?} bitboards overlap or disagree with the occupancy",
+                color
+            ));
+        }
+        if bitboards[PieceType::King as usize].popcount() != 1 {
+            return Err(format!("{:?} doesn't have exactly one king", color));
+        }
+    }
+    if !(pieces.occupancy(Color::White) & pieces.occupancy(Color::Black)).is_empty() {
+        return Err("a square holds both colors".to_owned());
+    }
+    for square in (0..64).map(Square) {
+        let found = [Color::White, Color::Black].into_iter().find_map(|color| {
+            let index = pieces
+                .bitboards(color)
+                .iter()
+                .position(|bb| bb.contains(square))?;
+            Some(Piece {
+                color,
+                piece_type: PIECE_TYPES[index],
+            })
+        });
+        let mailbox = pieces.piece_on(square);
+        if found != mailbox {
+            return Err(format!(
+                "square {} is {:?} but the mailbox says {:?}",
+                square, found, mailbox
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn check_position(state: &Position) -> Result<(), String> {
+    let fen = state.fen();
+    check_pieces(state).map_err(|err| format!("{}: {}", fen, err))?;
+    let color = state.active_color;
+    let moves = state.moves(color);
+    for &piece_move in &moves {
+        let after = state.apply_move(piece_move);
+        if after.is_square_attacked(king_square(&after, color), after.active_color) {
+            return Err(format!(
+                "{}: {} leaves the king in danger",
+                fen,
+                piece_move.display()
+            ));
+        }
+        if !state.is_legal(piece_move) {
+            return Err(format!(
+                "{}: is_legal rejects {}",
+                fen,
+                piece_move.display()
+            ));
+        }
+        if state.gives_check(piece_move) != after.in_check() {
+            return Err(format!(
+                "{}: gives_check is wrong about {}",
+                fen,
+                piece_move.display()
+            ));
+        }
+    }
+    reference::compare(state)
+}
+
+// plays from the start position, each choice picking one of the legal moves
+pub fn check_playout(choices: &[u8]) -> Result<(), String> {
+    let mut state = Position::default();
+    for &choice in choices {
+        check_position(&state)?;
+        let moves = state.moves(state.active_color);
+        if moves.is_empty() {
+            return Ok(());
+        }
+        state = state.apply_move(moves[choice as usize % moves.len()]);
+    }
+    check_position(&state)
+}
@@ -0,0 +1,1011 @@
+//! A bitboard chess engine: legal move generation, FEN and SAN notation, and an
+//! alpha-beta search that also speaks UCI.
+//!
+//! - [`position`]: [`Position`] and making moves on it
+//! - [`movegen`]: the legal moves of a position and perft
+//! - [`fen`] and [`san`]: reading and writing positions and moves
+//! - [`search`]: iterative deepening search, single or multi threaded
+//! - [`time_management`]: time limits for a search from a UCI clock
+//! - [`uci`]: the UCI protocol loop the `bitboard_generator` binary runs
+//!
+//! ```
+//! use bitboard_generator::{san, search::Searcher, Position};
+//!
+//! let position: Position = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1".parse().unwrap();
+//! let result = Searcher::new().iterate(&position, 3).unwrap();
+//! assert_eq!(san::format(&position, result.best_move), "Ra8#");
+//! ```
+
+#![warn(missing_docs)]
+
+pub mod fen;
+pub mod movegen;
+pub mod position;
+pub mod san;
+pub mod search;
+pub mod time_management;
+pub mod types;
+pub mod uci;
+
+// the binary's subcommands, not part of the stable API
+#[doc(hidden)]
+pub mod cli;
+mod datagen;
+mod evaluation;
+#[cfg(any(test, fuzzing))]
+#[doc(hidden)]
+pub mod invariants;
+mod move_ordering;
+#[cfg(feature = "nnue")]
+mod nnue;
+mod pawns;
+mod perft;
+mod perft_debug;
+#[cfg(any(test, fuzzing, feature = "reference"))]
+mod reference;
+mod sliding_pieces;
+mod transposition;
+// the tune binary's Texel tuner, not part of the stable API
+#[doc(hidden)]
+pub mod tune;
+mod util;
+mod weights;
+mod zobrist;
+
+pub use crate::position::Position;
+pub use crate::types::{Color, Direction, Move, Piece, PieceType, PromotionType};
+pub use crate::util::{Bitboard, Square};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_divide() {
+        // let fen = "3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1".to_owned();
+        // let game: Position = Position::new(fen);
+        let game = Position::default();
+        // let game = game.apply_move(Move {
+        //     from: 39,
+        //     to: 47,
+        //     piece_color: game.active_color,
+        //     promoted_piece: None,
+        // });
+        // let game = game.apply_move(Move {
+        //     from: 32,
+        //     to: 33,
+        //     piece_color: game.active_color,
+        //     promoted_piece: None,
+        // });
+        // let game = game.apply_move(Move {
+        //     from: 47,
+        //     to: 42,
+        //     piece_color: game.active_color,
+        //     promoted_piece: None,
+        // });
+        // let game = game.apply_move(Move {
+        //     from: 33,
+        //     to: 26,
+        //     piece_color: game.active_color,
+        //     promoted_piece: None,
+        // });
+        // let game = game.apply_move(Move {
+        //     from: 35,
+        //     to: 27,
+        //     piece_color: game.active_color,
+        //     promoted_piece: None,
+        // });
+        let counts = perft::divide(&game, 4, 1, None);
+        assert_eq!(counts.len(), 20);
+        assert_eq!(counts.iter().map(|(_, nodes)| nodes).sum::<u64>(), 197281);
+        // threads and the hash table don't change the counts
+        let table = perft::PerftTable::new(1);
+        assert_eq!(perft::divide(&game, 4, 3, Some(&table)), counts);
+        assert_eq!(perft::perft(&game, 4, Some(&table)), 197281);
+    }
+
+    // (fen, [(depth, nodes)]) from the "<fen> ;D1 <nodes> ;D2 <nodes>" lines of perft_suite.epd,
+    // other opcodes such as c0 comments are skipped
+    fn perft_suite() -> Vec<(String, Vec<(usize, u64)>)> {
+        include_str!("perft_suite.epd")
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(';');
+                let fen = fields.next()?.trim().to_owned();
+                let counts = fields
+                    .filter_map(|field| {
+                        let (depth, nodes) = field.trim().strip_prefix('D')?.split_once(' ')?;
+                        Some((depth.parse().ok()?, nodes.parse().ok()?))
+                    })
+                    .collect();
+                Some((fen, counts))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_movegen_is_color_symmetric() {
+        // White and Black run the same generic routines, so mirrored positions
+        // must give the same counts
+        for (fen, counts) in perft_suite() {
+            if fen.split(' ').nth(3) != Some("-") {
+                continue;
+            }
+            let mirrored = Position::new(flip_fen(&format!("{} 0 1", fen)));
+            for (depth, nodes) in counts.into_iter().filter(|&(_, n)| n <= 2000) {
+                assert_eq!(mirrored.perft(depth), nodes, "{} depth {}", fen, depth);
+            }
+        }
+    }
+
+    #[test]
+    fn test_perft_suite() {
+        // every depth small enough to run in a debug build; see test_perft_suite_deep
+        const FAST_NODES: u64 = 5000;
+        let suite = perft_suite();
+        assert!(suite.len() > 140);
+        let table = perft::PerftTable::new(16);
+        for (fen, counts) in suite {
+            // every line needs a shallow count so the default run covers it
+            assert!(counts.iter().any(|&(_, n)| n <= FAST_NODES), "{}", fen);
+            let game = Position::new(fen.clone());
+            for (depth, nodes) in counts.into_iter().filter(|&(_, n)| n <= FAST_NODES) {
+                assert_eq!(
+                    perft::perft(&game, depth, Some(&table)),
+                    nodes,
+                    "{} depth {}",
+                    fen,
+                    depth
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "takes hours in a debug build, run with --release"]
+    fn test_perft_suite_deep() {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let table = perft::PerftTable::new(256);
+        for (fen, counts) in perft_suite() {
+            let game = Position::new(fen.clone());
+            for (depth, nodes) in counts {
+                let total: u64 = perft::divide(&game, depth, threads, Some(&table))
+                    .iter()
+                    .map(|(_, nodes)| nodes)
+                    .sum();
+                assert_eq!(total, nodes, "{} depth {}", fen, depth);
+            }
+        }
+    }
+
+    #[test]
+    fn test_special_moves_update_the_board() {
+        let play = |fen: &str, moves: &[&str]| {
+            let mut game = Position::new(fen.to_owned());
+            for text in moves {
+                let piece_move = game
+                    .moves(game.active_color)
+                    .into_iter()
+                    .find(|m| m.display() == *text)
+                    .unwrap_or_else(|| panic!("{} not legal in {}", text, game.fen()));
+                game = game.apply_move(piece_move);
+            }
+            game.fen()
+        };
+        let castling = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!(
+            play(castling, &["e1g1"]),
+            "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1"
+        );
+        assert_eq!(
+            play(castling, &["e1g1", "e8c8"]),
+            "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2"
+        );
+        assert_eq!(
+            play(castling, &["a1a8"]),
+            "R3k2r/8/8/8/8/8/8/4K2R b Kk - 0 1"
+        );
+        assert_eq!(
+            play("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", &["b7b8n"]),
+            "1N2k3/8/8/8/8/8/8/4K3 b - - 0 1"
+        );
+        assert_eq!(
+            play("4k3/8/8/8/1p6/8/P7/4K3 w - - 0 1", &["a2a4", "b4a3"]),
+            "4k3/8/8/8/8/p7/8/4K3 w - - 0 2"
+        );
+    }
+
+    #[test]
+    fn test_perft_debug_finds_the_extra_move() {
+        use std::collections::BTreeMap;
+        // our own counts, except that g8f6 doesn't exist after e2e4
+        struct Faulty;
+        fn count(state: &Position, moves: &mut Vec<String>, depth: usize) -> BTreeMap<String, u64> {
+            let mut counts = BTreeMap::new();
+            for piece_move in state.moves(state.active_color) {
+                let text = piece_move.display();
+                if moves == &["e2e4"] && text == "g8f6" {
+                    continue;
+                }
+                let child = state.apply_move(piece_move);
+                moves.push(text.clone());
+                let nodes = match depth {
+                    1 => 1,
+                    _ => count(&child, moves, depth - 1).values().sum(),
+                };
+                moves.pop();
+                counts.insert(text, nodes);
+            }
+            counts
+        }
+        impl perft_debug::Reference for Faulty {
+            fn divide(
+                &mut self,
+                fen: &str,
+                moves: &[String],
+                depth: usize,
+            ) -> std::io::Result<BTreeMap<String, u64>> {
+                let mut state = Position::new(fen.to_owned());
+                for text in moves {
+                    state = state.apply_move(uci::parse_move(&state, text, false).unwrap());
+                }
+                Ok(count(&state, &mut moves.to_vec(), depth))
+            }
+        }
+        let fen = fen::START_FEN;
+        let mut steps = vec![];
+        let discrepancy =
+            perft_debug::find_discrepancy(&mut Faulty, fen, 3, |text, ours, theirs| {
+                steps.push((text.to_owned(), ours, theirs))
+            })
+            .unwrap()
+            .unwrap();
+        // perft 2 after e2e4 is 600, 30 of it below the missing g8f6
+        assert_eq!(steps, [("e2e4".to_owned(), 600, 570)]);
+        assert_eq!(discrepancy.moves, ["e2e4"]);
+        assert_eq!(discrepancy.extra, ["g8f6"]);
+        assert!(discrepancy.missing.is_empty());
+        assert!(discrepancy
+            .fen
+            .starts_with("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b"));
+        assert!(
+            perft_debug::find_discrepancy(&mut Faulty, fen, 1, |_, _, _| {})
+                .unwrap()
+                .is_none()
+        );
+        let bad_fen = "8/8/8/8/8/8/8/8 w - - 0 1";
+        let error =
+            perft_debug::find_discrepancy(&mut Faulty, bad_fen, 1, |_, _, _| {}).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    proptest::proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(48))]
+        #[test]
+        fn prop_random_playouts_keep_movegen_invariants(
+            choices in proptest::collection::vec(proptest::num::u8::ANY, 0..80)
+        ) {
+            if let Err(err) = invariants::check_playout(&choices) {
+                return Err(proptest::test_runner::TestCaseError::fail(err));
+            }
+        }
+    }
+
+    #[test]
+    fn test_reference_moves_match_perft_positions() {
+        for (fen, _) in perft_suite().into_iter().take(20) {
+            invariants::check_position(&Position::new(fen)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_reference_generator_agrees() {
+        assert_eq!(reference::differential(3000, 42), Ok(3000));
+        // the unfiltered list includes moves into check
+        let checked = Position::new("4k3/8/8/8/8/8/8/4K2r w - - 0 1".to_owned());
+        assert!(
+            reference::pseudo_legal_moves(&checked).len() > reference::legal_moves(&checked).len()
+        );
+    }
+
+    #[test]
+    fn test_square_and_bitboard() {
+        let e4: Square = "e4".parse().unwrap();
+        assert_eq!(
+            (e4.file(), e4.rank(), e4.to_string()),
+            (4, 3, "e4".to_owned())
+        );
+        assert_eq!(e4.flip().to_string(), "e5");
+        assert!("i1".parse::<Square>().is_err() && "e9".parse::<Square>().is_err());
+
+        let corners: Bitboard = ["a1", "h1", "a8", "h8"]
+            .iter()
+            .map(|square| square.parse::<Square>().unwrap())
+            .collect();
+        assert_eq!(corners.popcount(), 4);
+        assert_eq!(corners.lsb(), Some(Square(0)));
+        assert_eq!(corners.into_iter().last(), Some(Square(63)));
+        // nothing wraps onto the other side of the board
+        assert_eq!(
+            corners.shift(Direction::East),
+            Square(1).bitboard() | Square(57).bitboard()
+        );
+        assert_eq!(corners.shift(Direction::NorthEast), Square(9).bitboard());
+        assert_eq!(!corners & corners, Bitboard::EMPTY);
+    }
+
+    #[test]
+    fn test_pieces_helpers_stay_in_sync() {
+        let mut pieces = Position::default().pieces;
+        let (e2, e4): (Square, Square) = ("e2".parse().unwrap(), "e4".parse().unwrap());
+        let pawn = Piece {
+            color: Color::White,
+            piece_type: PieceType::Pawn,
+        };
+        assert_eq!(pieces.piece_on(e2), Some(pawn));
+        pieces.move_piece(e2, e4);
+        assert_eq!(
+            (pieces.piece_on(e2), pieces.piece_on(e4)),
+            (None, Some(pawn))
+        );
+        assert!(pieces.bitboard(Color::White, PieceType::Pawn).contains(e4));
+        assert!(!pieces.occupancy(Color::White).contains(e2));
+
+        assert_eq!(pieces.remove_piece(e4), Some(pawn));
+        assert_eq!(pieces.remove_piece(e4), None);
+        assert_eq!(pieces.occupancy(Color::White).popcount(), 15);
+        assert_eq!(pieces.occupied().popcount(), 31);
+    }
+
+    #[test]
+    fn test_move_queries() {
+        for (fen, _) in perft_suite() {
+            let game = Position::new(fen.clone());
+            let legal = game.legal_moves();
+            // every from/to pair, as the right or wrong side, with and without
+            // promotion or the castling flag
+            for (from, to) in (0..64).flat_map(|from| (0..64).map(move |to| (from, to))) {
+                for piece_color in [Color::White, Color::Black] {
+                    for (promoted_piece, castling) in [
+                        (None, false),
+                        (Some(PromotionType::Queen), false),
+                        (None, true),
+                    ] {
+                        let piece_move = Move {
+                            from: Square(from),
+                            to: Square(to),
+                            promoted_piece,
+                            piece_color,
+                            castling,
+                        };
+                        assert_eq!(
+                            game.is_legal(piece_move),
+                            legal.contains(&piece_move),
+                            "{} {}",
+                            fen,
+                            piece_move.display()
+                        );
+                    }
+                }
+            }
+            for &piece_move in &legal {
+                let checks = game.apply_move(piece_move).in_check();
+                assert_eq!(
+                    game.gives_check(piece_move),
+                    checks,
+                    "{} {}",
+                    fen,
+                    piece_move.display()
+                );
+            }
+        }
+        let checks = |fen: &str, text: &str| {
+            let game = Position::new(fen.to_owned());
+            game.gives_check(uci::parse_move(&game, text, false).unwrap())
+        };
+        // direct, discovered, en passant uncovering a rook and castling into a rook check
+        assert!(checks("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1a8"));
+        assert!(checks("4k3/8/8/8/8/8/4N3/4R1K1 w - - 0 1", "e2c3"));
+        assert!(checks("8/8/8/R2pP2k/8/8/8/4K3 w - d6 0 1", "e5d6"));
+        assert!(checks("5k2/8/8/8/8/8/8/4K2R w K - 0 1", "e1g1"));
+        assert!(!checks("4k3/8/8/8/8/8/4N3/4R1K1 w - - 0 1", "g1f2"));
+    }
+
+    #[test]
+    fn test_attack_queries() {
+        for (fen, _) in perft_suite() {
+            let game = Position::new(fen.clone());
+            for square in (0..64).map(Square) {
+                for by in [Color::White, Color::Black] {
+                    assert_eq!(
+                        game.is_square_attacked(square, by),
+                        reference::is_square_attacked(&game, square, by),
+                        "{} {} by {:?}",
+                        fen,
+                        square,
+                        by
+                    );
+                }
+            }
+        }
+        let bits = |squares: &[&str]| {
+            squares
+                .iter()
+                .map(|square| square.parse::<Square>().unwrap().bitboard())
+                .fold(Bitboard::EMPTY, |bb, square| bb | square)
+        };
+        // rook and queen batteries on the d-file, a knight and both pawns on d5
+        let game = Position::new("3qk3/3r4/2p2n2/3p4/4P3/8/3R4/3QK3 w - - 0 1".to_owned());
+        let d5 = "d5".parse().unwrap();
+        let occupied = game.pieces.occupied();
+        assert_eq!(
+            game.attackers_to(d5, occupied),
+            bits(&["d2", "e4", "c6", "f6", "d7"])
+        );
+        assert_eq!(game.xray_attackers(d5), bits(&["d1", "d8"]));
+        // taking the rook off lets the queen behind it through
+        let without_rook = occupied & !bits(&["d2"]);
+        assert_eq!(
+            game.attackers_to(d5, without_rook),
+            bits(&["d1", "e4", "c6", "f6", "d7"])
+        );
+    }
+
+    #[test]
+    fn test_fen_errors() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1",
+            "rnbq1bnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e4 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - zero 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w",
+            "4k3/4R3/8/8/8/8/8/4K3 w - - 0 1",
+        ] {
+            assert!(fen.parse::<Position>().is_err(), "{}", fen);
+        }
+        // the UCI loop reports a bad position instead of panicking
+        let tokens: Vec<&str> = "fen 8/8/8/8/8/8/8/8 w - - 0 1".split(' ').collect();
+        assert!(uci::parse_position(&tokens, false).is_none());
+        let kiwipete = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        assert_eq!(kiwipete.parse::<Position>().unwrap().fen(), kiwipete);
+    }
+
+    #[test]
+    fn test_san() {
+        let san = |fen: &str, uci: &str| {
+            let position = Position::new(fen.to_owned());
+            let piece_move = uci::parse_move(&position, uci, false).unwrap();
+            let text = san::format(&position, piece_move);
+            assert_eq!(san::parse(&position, &text), Ok(piece_move));
+            text
+        };
+        let kiwipete = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        assert_eq!(san(kiwipete, "e1g1"), "O-O");
+        assert_eq!(san(kiwipete, "e1c1"), "O-O-O");
+        assert_eq!(san(kiwipete, "e5f7"), "Nxf7");
+        assert_eq!(san(kiwipete, "d5e6"), "dxe6");
+        assert_eq!(san(kiwipete, "f3f6"), "Qxf6");
+        // both rooks reach b1, or a2
+        let rooks = "4k3/8/8/8/8/8/4K3/R6R w - - 0 1";
+        assert_eq!(san(rooks, "a1b1"), "Rab1");
+        assert_eq!(san(rooks, "h1h8"), "Rh8+");
+        let stacked = "4k3/8/8/8/R7/8/8/R3K3 w - - 0 1";
+        assert_eq!(san(stacked, "a1a2"), "R1a2");
+        let promotion = "8/P6k/8/8/8/8/8/K7 w - - 0 1";
+        assert_eq!(san(promotion, "a7a8q"), "a8=Q");
+        assert_eq!(san(promotion, "a7a8n"), "a8=N");
+        let en_passant = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1";
+        assert_eq!(san(en_passant, "e5d6"), "exd6");
+        let mate = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1";
+        assert_eq!(san(mate, "a1a8"), "Ra8#");
+
+        let start = Position::default();
+        assert_eq!(san::parse(&start, "Nf3!?").unwrap().display(), "g1f3");
+        assert!(san::parse(&start, "Nf4").is_err());
+        assert!(san::parse(&start, "O-O").is_err());
+        // every legal move round trips in the perft positions
+        for (fen, _) in perft_suite().into_iter().take(20) {
+            let position = Position::new(fen);
+            for piece_move in position.legal_moves() {
+                let text = san::format(&position, piece_move);
+                assert_eq!(san::parse(&position, &text), Ok(piece_move), "{}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_ordering_reduces_nodes() {
+        let nodes = search::BenchNodes::total(&search::bench(3));
+        assert!(nodes.full_width < nodes.unordered);
+    }
+
+    // a move that is neither a promotion nor castling, by square index
+    fn plain_move(from: u8, to: u8, piece_color: Color) -> Move {
+        Move {
+            from: Square(from),
+            to: Square(to),
+            piece_color,
+            promoted_piece: None,
+            castling: false,
+        }
+    }
+
+    #[test]
+    fn test_zobrist_transposition() {
+        let game = Position::default();
+        let a = game
+            .apply_move(plain_move(6, 21, Color::White))
+            .apply_move(plain_move(57, 42, Color::Black))
+            .apply_move(plain_move(1, 18, Color::White))
+            .apply_move(plain_move(62, 45, Color::Black));
+        let b = game
+            .apply_move(plain_move(1, 18, Color::White))
+            .apply_move(plain_move(62, 45, Color::Black))
+            .apply_move(plain_move(6, 21, Color::White))
+            .apply_move(plain_move(57, 42, Color::Black));
+        assert_eq!(a.hash, b.hash);
+        assert_ne!(a.hash, game.hash);
+        assert_ne!(game.hash, game.apply_null_move().hash);
+    }
+
+    #[test]
+    fn test_masks_follow_the_side_to_move() {
+        // White's masks are computed first, the null move must not reuse them
+        let game = Position::new("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1".to_owned());
+        assert_eq!(game.masks().castling, (true, false));
+        let passed = game.apply_null_move();
+        let black = Position::new("4k3/8/8/8/8/8/5r2/R3K2R b KQ - 0 1".to_owned());
+        assert_eq!(passed.legal_moves(), black.legal_moves());
+    }
+
+    #[test]
+    fn test_check_extension_finds_deeper_mate() {
+        let game = Position::new("2r3k1/5ppp/8/8/8/8/3R1PPP/3R2K1 w - - 0 1".to_owned());
+        let mut params = search::SearchParams::full_width();
+        let eval = search::Searcher::with_params(params)
+            .search(&game, 2)
+            .unwrap();
+        assert_eq!(eval.score, search::MATE - 3);
+        params.check_extensions = false;
+        let eval = search::Searcher::with_params(params)
+            .search(&game, 2)
+            .unwrap();
+        assert!(!search::is_mate_score(eval.score));
+    }
+
+    // swaps colors and mirrors the board vertically
+    fn flip_fen(fen: &str) -> String {
+        let parts: Vec<&str> = fen.split(' ').collect();
+        let swap_case = |s: &str| -> String {
+            s.chars()
+                .map(|c| {
+                    if c.is_uppercase() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c.to_ascii_uppercase()
+                    }
+                })
+                .collect()
+        };
+        let board: Vec<&str> = parts[0].split('/').rev().collect();
+        let side = if parts[1] == "w" { "b" } else { "w" };
+        format!(
+            "{} {} {} - {} {}",
+            swap_case(&board.join("/")),
+            side,
+            swap_case(parts[2]),
+            parts[4],
+            parts[5]
+        )
+    }
+
+    #[test]
+    fn test_evaluation_is_symmetric() {
+        assert_eq!(Position::default().static_eval(), 0);
+        for fen in search::BENCH_POSITIONS {
+            let game = Position::new(fen.to_owned());
+            let flipped = Position::new(flip_fen(fen));
+            assert_eq!(game.static_eval(), flipped.static_eval(), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_evaluation_signed_and_traced() {
+        // black is a queen up, counts subtract without underflowing
+        let game = Position::new("3qk3/8/8/8/8/8/8/4K3 w - - 0 1".to_owned());
+        let trace = evaluation::trace(&game);
+        assert!(trace.score < -800);
+        assert_eq!(game.static_eval(), trace.score);
+        assert_eq!(trace.phase, 4);
+        assert!(trace.terms.iter().any(|term| term.name == "material"));
+    }
+
+    #[test]
+    fn test_pawn_structure_terms() {
+        // white: doubled, isolated and passed c-pawns, backward e-pawn; black: f/g/h phalanx
+        let game = Position::new("6k1/5ppp/8/4P3/8/2P5/2P5/6K1 w - - 0 1".to_owned());
+        let terms = pawns::evaluate(&game.pieces);
+        let [passed, isolated, doubled, backward, connected] = terms.white;
+        assert_eq!(passed, evaluation::Score(10, 15));
+        assert_eq!(isolated, evaluation::Score(-30, -45));
+        assert_eq!(doubled, evaluation::Score(-10, -25));
+        assert_eq!(backward, evaluation::Score(-8, -10));
+        assert_eq!(terms.black[1], evaluation::Score::default());
+        assert!(connected == evaluation::Score::default() && terms.black[4].0 > 0);
+    }
+
+    #[test]
+    fn test_pawn_hash_caches_structure() {
+        let game = Position::default();
+        let knight = game.apply_move(plain_move(6, 21, Color::White));
+        assert_eq!(game.pawn_hash, knight.pawn_hash);
+        let pawn = knight.apply_move(plain_move(52, 36, Color::Black));
+        assert_ne!(knight.pawn_hash, pawn.pawn_hash);
+        let mut evaluator = evaluation::Evaluator::new();
+        for state in [&game, &knight, &pawn, &knight] {
+            assert_eq!(evaluator.evaluate(state), state.static_eval());
+        }
+    }
+
+    #[test]
+    fn test_king_safety_terms() {
+        // white queen and knight bear down on a king stripped of its shield
+        let game = Position::new("6k1/5p2/6pQ/6N1/8/8/5PPP/2B1B1K1 b - - 0 1".to_owned());
+        let trace = evaluation::trace(&game);
+        let term = |name| trace.terms.iter().find(|term| term.name == name).unwrap();
+        assert!(term("king attack").white.0 > 0);
+        assert_eq!(term("king attack").black, evaluation::Score::default());
+        assert!(term("pawn shield").white.0 > term("pawn shield").black.0);
+        assert_eq!(term("bishop pair").white, evaluation::Score(50, 50));
+    }
+
+    #[cfg(feature = "nnue")]
+    fn random_network_bytes(hidden: usize) -> Vec<u8> {
+        let mut state = 0x5eed;
+        let mut bytes = vec![];
+        for _ in 0..hidden * 771 + 1 {
+            let (next, value) = zobrist::splitmix64(state);
+            state = next;
+            bytes.extend_from_slice(&((value % 129) as i16 - 64).to_le_bytes());
+        }
+        bytes
+    }
+
+    #[cfg(feature = "nnue")]
+    #[test]
+    fn test_nnue_network_format() {
+        let mut bytes = random_network_bytes(40);
+        bytes.extend_from_slice(&[0; 32]);
+        assert!(nnue::Network::from_bytes(&bytes).is_ok());
+        assert!(nnue::Network::from_bytes(&bytes[..1000]).is_err());
+        let values: Vec<i16> = (0..40).map(|i| i * 17 - 300).collect();
+        let weights: Vec<i16> = (0..40).map(|i| 50 - i * 3).collect();
+        assert_eq!(
+            nnue::crelu_dot(&values, &weights),
+            nnue::crelu_dot_scalar(&values, &weights)
+        );
+    }
+
+    #[cfg(feature = "nnue")]
+    #[test]
+    fn test_nnue_incremental_matches_refresh() {
+        let network = nnue::Network::from_bytes(&random_network_bytes(40)).unwrap();
+        let mut game = Position::new(search::BENCH_POSITIONS[1].to_owned());
+        let mut accumulator = nnue::Accumulator::refresh(&network, &game.pieces);
+        for ply in 0..16 {
+            let moves = game.moves(game.active_color);
+            let Some(&piece_move) = moves
+                .iter()
+                .find(|&&m| move_ordering::is_capture(&game, m))
+                .or(moves.get(ply % moves.len().max(1)))
+            else {
+                break;
+            };
+            let next = game.apply_move(piece_move);
+            accumulator.update(&network, &game.pieces, &next.pieces);
+            assert_eq!(
+                accumulator,
+                nnue::Accumulator::refresh(&network, &next.pieces)
+            );
+            game = next;
+        }
+        // both perspectives see the same position in a colour-flipped board
+        for fen in search::BENCH_POSITIONS {
+            let game = Position::new(fen.to_owned());
+            let flipped = Position::new(flip_fen(fen));
+            let eval = |state: &Position| {
+                let accumulator = nnue::Accumulator::refresh(&network, &state.pieces);
+                nnue::evaluate(&network, &accumulator, state.active_color)
+            };
+            assert_eq!(eval(&game), eval(&flipped), "{}", fen);
+        }
+    }
+
+    #[cfg(feature = "nnue")]
+    #[test]
+    fn test_nnue_evaluation_scale() {
+        // one hidden neuron: bias 100, +60 per own pawn, output weights +1.0 for
+        // the side to move and -1.0 for the other, output bias +1.0
+        let eval = |output: [i16; 3], fen: &str| {
+            let mut words = vec![0i16; 768];
+            words[..64].fill(60);
+            words.push(100);
+            words.extend_from_slice(&output);
+            let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+            let network = nnue::Network::from_bytes(&bytes).unwrap();
+            let state = Position::new(fen.to_owned());
+            let accumulator = nnue::Accumulator::refresh(&network, &state.pieces);
+            nnue::evaluate(&network, &accumulator, state.active_color)
+        };
+        // White's neuron is 100 + 3 * 60 = 280, clipped to 255, Black's is 100:
+        // (255 - 100 + 255) / 255 * 400 and (100 - 255 + 255) / 255 * 400
+        let output = [64, -64, 255 * 64];
+        assert_eq!(eval(output, "4k3/8/8/8/8/8/PPP5/4K3 w - - 0 1"), 643);
+        assert_eq!(eval(output, "4k3/8/8/8/8/8/PPP5/4K3 b - - 0 1"), 156);
+        // (255 + 100 + 1) * 32767 * 400 overflows i32, the score stays below mate
+        let output = [i16::MAX; 3];
+        let score = eval(output, "4k3/8/8/8/8/8/PPP5/4K3 w - - 0 1");
+        assert!(score > 0 && !search::is_mate_score(score), "{}", score);
+    }
+
+    #[test]
+    fn test_tuner_matches_evaluation() {
+        let params = tune::initial_params();
+        assert_eq!(tune::write_source(&params), include_str!("weights.rs"));
+        let mut entries = vec![];
+        for fen in search::BENCH_POSITIONS {
+            let game = Position::new(fen.to_owned());
+            let entry = tune::Entry::new(&game, 0.5);
+            let score = evaluation::trace(&game).score as f64;
+            assert!((entry.evaluate(&params) - score).abs() <= 1.0, "{}", fen);
+            entries.push(entry);
+        }
+        // a won and a lost position pull the weights apart
+        let lines = [
+            "3qk3/8/8/8/8/8/8/4K3 w - - 0 1 [0-1]",
+            "4k3/8/8/8/8/8/8/3QK3 b - - c9 \"1-0\";",
+        ];
+        entries.extend(tune::read(lines.join("\n").as_bytes()).unwrap());
+        assert_eq!(entries.len(), search::BENCH_POSITIONS.len() + 2);
+        // a labelled line whose FEN doesn't parse is reported by number
+        let bad = [lines[0], "8/8/8/8/8/8/8/8 w - - 0 1 [1-0]"].join("\n");
+        let Err(error) = tune::read(bad.as_bytes()) else {
+            panic!("{:?} parsed", bad);
+        };
+        assert!(error.to_string().starts_with("line 2:"), "{}", error);
+        let mut params = params;
+        let before = tune::mean_squared_error(&entries, &params, 1.0);
+        let mut after = before;
+        tune::tune(&entries, &mut params, 1.0, 20, |_, error| after = error);
+        assert!(after < before);
+    }
+
+    #[test]
+    fn test_datagen_is_deterministic() {
+        let config = datagen::DatagenConfig {
+            games: 3,
+            threads: 1,
+            depth: 3,
+            nodes: Some(300),
+            random_plies: 6,
+            max_plies: 12,
+            seed: 42,
+        };
+        let mut single = vec![];
+        datagen::run(config, &mut single).unwrap();
+        let mut threaded = vec![];
+        let threads = datagen::DatagenConfig {
+            threads: 3,
+            ..config
+        };
+        datagen::run(threads, &mut threaded).unwrap();
+        assert_eq!(single, threaded);
+
+        let text = String::from_utf8(single).unwrap();
+        assert!(text.lines().count() > 0);
+        for line in text.lines() {
+            let (fen, result) = tune::parse_line(line).unwrap();
+            assert_eq!(result, 0.5);
+            let game = Position::new(fen.clone());
+            assert_eq!(game.fen(), fen);
+            assert!(!game.in_check());
+        }
+    }
+
+    #[test]
+    fn test_time_management() {
+        use std::time::{Duration, Instant};
+        use time_management::{Clock, TimeManager};
+
+        let ms = Duration::from_millis;
+        assert!(TimeManager::new(&Clock::default(), Color::White, 30).is_none());
+        let clock = Clock {
+            wtime: Some(60_000),
+            btime: Some(1_000),
+            winc: 1_000,
+            ..Clock::default()
+        };
+        // 59970ms over 30 moves plus 3/4 of the increment, hard limit four times that
+        let white = TimeManager::new(&clock, Color::White, 30).unwrap();
+        assert_eq!(
+            (white.soft_limit(0.0, 0), white.hard_limit()),
+            (ms(2749), ms(10996))
+        );
+        // black's clock is nearly gone: 970 / 30 = 32ms a move
+        let black = TimeManager::new(&clock, Color::Black, 30).unwrap();
+        assert_eq!(
+            (black.soft_limit(0.0, 0), black.hard_limit()),
+            (ms(32), ms(128))
+        );
+        // a falling score or a changing best move stretch the soft limit, up to 2.5x
+        assert_eq!(black.soft_limit(0.0, 100), ms(48));
+        assert_eq!(black.soft_limit(5.0, 0), ms(80));
+        // with one move to go the hard limit leaves a quarter of the clock
+        let last_move = Clock {
+            wtime: Some(1_000),
+            movestogo: Some(1),
+            ..Clock::default()
+        };
+        let last_move = TimeManager::new(&last_move, Color::White, 0).unwrap();
+        assert_eq!(
+            (last_move.soft_limit(5.0, 0), last_move.hard_limit()),
+            (ms(750), ms(750))
+        );
+        let movetime = Clock {
+            movetime: Some(100),
+            ..Clock::default()
+        };
+        let second_ago = Instant::now() - Duration::from_secs(1);
+        let spent = TimeManager::starting_at(second_ago, &movetime, Color::White, 30).unwrap();
+        assert_eq!(
+            (spent.soft_limit(5.0, 0), spent.hard_limit()),
+            (ms(70), ms(70))
+        );
+
+        // a spent clock still returns the first iteration, and stops after it
+        assert!(spent.hard_expired() && spent.soft_expired(0.0, 0));
+        let game = Position::new(search::BENCH_POSITIONS[1].to_owned());
+        let mut searcher = search::Searcher::new();
+        searcher.time = Some(spent);
+        let eval = searcher.iterate(&game, search::MAX_PLY - 1).unwrap();
+        assert_eq!(searcher.completed_depth, 1);
+        assert!(game.moves(Color::White).contains(&eval.best_move));
+
+        // pondering ignores the clock, a spent movetime still reaches the depth
+        searcher
+            .pondering
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        searcher.iterate(&game, 3).unwrap();
+        assert_eq!(searcher.completed_depth, 3);
+        searcher
+            .pondering
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+
+        // a search stopped mid-iteration keeps the last complete one
+        searcher.time = None;
+        searcher
+            .stop
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(searcher.iterate(&game, search::MAX_PLY - 1).is_some());
+    }
+
+    #[test]
+    fn test_selective_search_reduces_nodes() {
+        let nodes = search::BenchNodes::total(&search::bench(4));
+        assert!(nodes.selective < nodes.full_width);
+    }
+
+    #[test]
+    fn test_pv_finds_mate() {
+        let game = Position::new("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1".to_owned());
+        let mut searcher = search::Searcher::new();
+        let eval = searcher.iterate(&game, 4).unwrap();
+        assert_eq!(eval.best_move.display(), "a1a8");
+        assert_eq!(eval.pv[0], eval.best_move);
+        assert!(
+            search::info_line(4, eval.score, searcher.nodes, &eval.pv, false).contains("mate 1")
+        );
+    }
+
+    #[test]
+    fn test_transposition_entries_roundtrip() {
+        use transposition::{Bound, TranspositionTable};
+        let tt = TranspositionTable::new(1);
+        let promotion = Move {
+            from: "e7".parse().unwrap(),
+            to: "f8".parse().unwrap(),
+            promoted_piece: Some(PromotionType::Knight),
+            piece_color: Color::Black,
+            castling: false,
+        };
+        for (key, bound) in [(1, Bound::Exact), (2, Bound::Lower), (3, Bound::Upper)] {
+            tt.store(key, Some(promotion), -(search::MATE - 3), 17, bound);
+            let entry = tt.probe(key).unwrap();
+            assert_eq!(entry.best_move, Some(promotion));
+            assert_eq!(
+                (entry.score, entry.depth, entry.bound),
+                (-(search::MATE - 3), 17, bound)
+            );
+        }
+        // a store without a move keeps the old one
+        tt.store(1, None, 5, 3, Bound::Upper);
+        assert_eq!(tt.probe(1).unwrap().best_move, Some(promotion));
+        assert!(tt.probe(4).is_none());
+        let castling = Move {
+            from: "e1".parse().unwrap(),
+            to: "h1".parse().unwrap(),
+            promoted_piece: None,
+            piece_color: Color::White,
+            castling: true,
+        };
+        tt.store(5, Some(castling), 0, 1, Bound::Exact);
+        assert_eq!(tt.probe(5).unwrap().best_move, Some(castling));
+        // but a shallower store with a move replaces it
+        tt.store(1, Some(castling), 0, 1, Bound::Lower);
+        assert_eq!(tt.probe(1).unwrap().best_move, Some(castling));
+    }
+
+    #[test]
+    fn test_chess960() {
+        assert_eq!(fen::chess960_fen(518).unwrap(), fen::START_FEN);
+        assert_eq!(
+            fen::chess960_fen(0).unwrap(),
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+        );
+        assert_eq!(
+            fen::chess960_fen(959).unwrap().split('/').next(),
+            Some("rkrnnqbb")
+        );
+        assert!(fen::chess960_fen(960).is_none());
+        // Shredder-FEN file letters read the same as X-FEN, which is what fen() writes
+        let shredder = Position::new(
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9".to_owned(),
+        );
+        assert_eq!(
+            shredder.fen(),
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+        );
+        // an inner rook needs its file letter
+        let inner = Position::new("4k3/8/8/8/8/8/8/4K1RR w G - 0 1".to_owned());
+        assert_eq!(inner.fen(), "4k3/8/8/8/8/8/8/4K1RR w G - 0 1");
+        let castle = uci::parse_move(&inner, "e1g1", true).unwrap();
+        assert!(castle.is_castling());
+        assert_eq!(castle.uci(false), "e1g1");
+        assert_eq!(
+            inner.apply_move(castle).fen(),
+            "4k3/8/8/8/8/8/8/5RKR b - - 1 1"
+        );
+        // b1c1 is a king step, castling is b1a1 and leaves the king on c1
+        let queenside = Position::new("4k3/8/8/8/8/8/8/RK6 w A - 0 1".to_owned());
+        assert!(!uci::parse_move(&queenside, "b1c1", true)
+            .unwrap()
+            .is_castling());
+        let castle = uci::parse_move(&queenside, "b1a1", true).unwrap();
+        assert_eq!(san::format(&queenside, castle), "O-O-O");
+        assert_eq!(
+            queenside.apply_move(castle).fen(),
+            "4k3/8/8/8/8/8/8/2KR4 b - - 1 1"
+        );
+        // the castling rook no longer shields the king from the a1 rook
+        let shielded = Position::new("4k3/8/8/8/8/8/8/rRK5 w B - 0 1".to_owned());
+        assert!(shielded.legal_moves().iter().all(|m| !m.is_castling()));
+        for index in [0, 1, 518, 959] {
+            let start = Position::new(fen::chess960_fen(index).unwrap());
+            assert_eq!(start.perft(2), 400);
+            invariants::check_position(&start).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_lazy_smp_finds_mate() {
+        let game = Position::new("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1".to_owned());
+        let mut searcher = search::Searcher::new();
+        let eval = searcher.iterate_smp(&game, 4, 3).unwrap();
+        assert_eq!(eval.best_move.display(), "a1a8");
+        assert!(searcher.completed_depth >= 4);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::search::MAX_PLY;
+use crate::util::Square;
+use crate::{Color, Move, PieceType, Position};
+
+const TT_MOVE_SCORE: i32 = 2_000_000;
+const CAPTURE_SCORE: i32 = 1_000_000;
+const PROMOTION_SCORE: i32 = 900_000;
+const KILLER_SCORE: [i32; 2] = [800_000, 790_000];
+const COUNTERMOVE_SCORE: i32 = 700_000;
+pub const MAX_HISTORY: i32 = 16_384;
+
+pub fn mvv_lva_value(piece: PieceType) -> i32 {
+    match piece {
+        PieceType::King => 20,
+        PieceType::Queen => 9,
+        PieceType::Rook => 5,
+        PieceType::Bishop => 3,
+        PieceType::Knight => 3,
+        PieceType::Pawn => 1,
+    }
+}
+
+fn piece_type_on(state: &Position, square: Square) -> Option<PieceType> {
+    state.pieces.piece_on(square).map(|piece| piece.piece_type)
+}
+
+pub fn is_capture(state: &Position, piece_move: Move) -> bool {
+    state.pieces.piece_on(piece_move.to).is_some()
+        || (piece_type_on(state, piece_move.from) == Some(PieceType::Pawn)
+            && state.en_passant == Some(piece_move.to))
+}
+
+pub fn is_quiet(state: &Position, piece_move: Move) -> bool {
+    piece_move.promoted_piece.is_none() && !is_capture(state, piece_move)
+}
+
+// history gravity: entries saturate towards +-MAX_HISTORY instead of overflowing
+fn apply_gravity(entry: &mut i32, bonus: i32) {
+    *entry += bonus - *entry * bonus.abs() / MAX_HISTORY;
+}
+
+pub struct MoveOrdering {
+    killers: [[Option<Move>; 2]; MAX_PLY],
+    history: Box<[[[i32; 64]; 64]; 2]>, // [color][from][to]
+    countermoves: Box<[[Option<Move>; 64]; 64]>, // [previous from][previous to]
+}
+
+impl MoveOrdering {
+    pub fn new() -> MoveOrdering {
+        MoveOrdering {
+            killers: [[None; 2]; MAX_PLY],
+            history: Box::new([[[0; 64]; 64]; 2]),
+            countermoves: Box::new([[None; 64]; 64]),
+        }
+    }
+    pub fn history(&self, color: Color, piece_move: Move) -> i32 {
+        self.history[color as usize][piece_move.from][piece_move.to]
+    }
+    pub fn countermove(&self, previous: Option<Move>) -> Option<Move> {
+        previous.and_then(|prev| self.countermoves[prev.from][prev.to])
+    }
+    pub fn score_move(
+        &self,
+        state: &Position,
+        piece_move: Move,
+        ply: usize,
+        previous: Option<Move>,
+        tt_move: Option<Move>,
+    ) -> i32 {
+        if tt_move == Some(piece_move) {
+            return TT_MOVE_SCORE;
+        }
+        if is_capture(state, piece_move) {
+            let victim = piece_type_on(state, piece_move.to).unwrap_or(PieceType::Pawn);
+            let attacker =
+                piece_type_on(state, piece_move.from).expect("move from an empty square");
+            return CAPTURE_SCORE + mvv_lva_value(victim) * 100 - mvv_lva_value(attacker);
+        }
+        if let Some(promotion) = piece_move.promoted_piece {
+            return PROMOTION_SCORE - promotion as i32;
+        }
+        let killers = self.killers[ply];
+        if killers[0] == Some(piece_move) {
+            return KILLER_SCORE[0];
+        }
+        if killers[1] == Some(piece_move) {
+            return KILLER_SCORE[1];
+        }
+        if self.countermove(previous) == Some(piece_move) {
+            return COUNTERMOVE_SCORE;
+        }
+        self.history(piece_move.piece_color, piece_move)
+    }
+    pub fn order(
+        &self,
+        state: &Position,
+        moves: Vec<Move>,
+        ply: usize,
+        previous: Option<Move>,
+        tt_move: Option<Move>,
+    ) -> Vec<Move> {
+        let mut scored: Vec<(i32, Move)> = moves
+            .into_iter()
+            .map(|piece_move| {
+                let score = self.score_move(state, piece_move, ply, previous, tt_move);
+                (score, piece_move)
+            })
+            .collect();
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        scored
+            .into_iter()
+            .map(|(_, piece_move)| piece_move)
+            .collect()
+    }
+    // called when a quiet move causes a beta cutoff; `tried` holds the quiets searched before it
+    pub fn update_quiet(
+        &mut self,
+        piece_move: Move,
+        ply: usize,
+        depth: usize,
+        previous: Option<Move>,
+        tried: &[Move],
+    ) {
+        if self.killers[ply][0] != Some(piece_move) {
+            self.killers[ply][1] = self.killers[ply][0];
+            self.killers[ply][0] = Some(piece_move);
+        }
+        if let Some(prev) = previous {
+            self.countermoves[prev.from][prev.to] = Some(piece_move);
+        }
+        let bonus = ((depth * depth) as i32).min(MAX_HISTORY / 8);
+        let color = piece_move.piece_color as usize;
+        apply_gravity(
+            &mut self.history[color][piece_move.from][piece_move.to],
+            bonus,
+        );
+        for quiet in tried {
+            apply_gravity(&mut self.history[color][quiet.from][quiet.to], -bonus);
+        }
+    }
+}
@@ -0,0 +1,685 @@
+//! Legal move generation.
+//!
+//! [`Position::legal_moves`] lists the moves of the side to move. It relies on
+//! masks computed for that side the first time a position needs them: the squares
+//! that block or capture a check, the pinned pieces and their pin lines, and the
+//! squares the enemy attacks, so no move has to be tried and taken back.
+//!
+//! ```
+//! use bitboard_generator::Position;
+//!
+//! let position = Position::default();
+//! assert_eq!(position.legal_moves().len(), 20);
+//! assert_eq!(position.perft(3), 8902);
+//! ```
+
+use std::sync::OnceLock;
+
+use crate::position::{castling_squares, Position};
+use crate::sliding_pieces::{
+    bishop_attacks, east_attacks, noea_attacks, nort_attacks, nowe_attacks, rook_attacks,
+    soea_attacks, sout_attacks, sowe_attacks, west_attacks,
+};
+use crate::types::{Color, Direction, Invert, Move, PieceType, PromotionType};
+use crate::util::{Bitboard, Square};
+
+// a color known at compile time, so each movegen routine is written once and
+// instantiated for both sides
+trait Side {
+    const COLOR: Color;
+    const THEM: Color;
+    const UP: Direction; // pawn pushes
+    const DOWN: Direction;
+    const BACK_RANK: u8;
+    const PAWN_RANK: u8;
+    const LAST_RANK: u8;
+}
+struct White;
+struct Black;
+impl Side for White {
+    const COLOR: Color = Color::White;
+    const THEM: Color = Color::Black;
+    const UP: Direction = Direction::North;
+    const DOWN: Direction = Direction::South;
+    const BACK_RANK: u8 = 0;
+    const PAWN_RANK: u8 = 1;
+    const LAST_RANK: u8 = 7;
+}
+impl Side for Black {
+    const COLOR: Color = Color::Black;
+    const THEM: Color = Color::White;
+    const UP: Direction = Direction::South;
+    const DOWN: Direction = Direction::North;
+    const BACK_RANK: u8 = 7;
+    const PAWN_RANK: u8 = 6;
+    const LAST_RANK: u8 = 0;
+}
+
+// what the side to move's movegen needs from the position, see Position::masks
+#[derive(Clone, Default)]
+pub(crate) struct Masks {
+    pub(crate) checkmask: Bitboard,
+    pub(crate) checkers: Bitboard,
+    pub(crate) pinmask: PinMask,
+    pub(crate) pinned: Bitboard,
+    pub(crate) king_danger: Bitboard,
+    pub(crate) castling: (bool, bool), // queenside, kingside
+}
+#[derive(Clone, Copy, Default)]
+pub(crate) struct PinMask {
+    h: Bitboard,
+    v: Bitboard,
+    d1: Bitboard,
+    d2: Bitboard,
+}
+
+pub(crate) static ALL_BITS: Bitboard = Bitboard::FULL;
+
+pub(crate) fn vision(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    rook_attacks(pieces, empty) | bishop_attacks(pieces, empty)
+}
+
+impl Position {
+    // occupancy and king squares after the pieces moved; the masks depend on them
+    // and are recomputed on first use
+    pub(crate) fn update_masks(&mut self) {
+        self.empty = !self.pieces.occupied();
+        self.king_square = [Color::White, Color::Black].map(|color| {
+            self.pieces
+                .bitboard(color, PieceType::King)
+                .lsb()
+                .expect("king")
+        });
+        self.masks = OnceLock::new();
+    }
+    // checks, pins, attacked squares and castling legality for the side to move,
+    // the only side whose moves are generated
+    pub(crate) fn masks(&self) -> &Masks {
+        self.masks.get_or_init(|| match self.active_color {
+            Color::White => self.side_masks::<White>(),
+            Color::Black => self.side_masks::<Black>(),
+        })
+    }
+    fn side_masks<S: Side>(&self) -> Masks {
+        let king = self.pieces.bitboard(S::COLOR, PieceType::King);
+        let [_, their_queen, their_rook, their_bishop, _, _] = self.pieces.bitboards(S::THEM);
+        let our_pieces = self.pieces.occupancy(S::COLOR);
+        let empty = self.empty;
+        let king_idx = self.king_square[S::COLOR as usize];
+        let straight = their_rook | their_queen;
+        let diagonal = their_bishop | their_queen;
+
+        let checkers = self.attackers_to(king_idx, !empty) & self.pieces.occupancy(S::THEM);
+        // a slider check is also answered by blocking its line
+        let mut checkmask = checkers;
+        for checker in checkers & (straight | diagonal) {
+            checkmask |= self.slide_lookup[&(king | checker.bitboard())];
+        }
+        checkmask &= !king;
+
+        let pinned = ((nort_attacks(king, empty) & sout_attacks(straight, empty))
+            | (noea_attacks(king, empty) & sowe_attacks(diagonal, empty))
+            | (east_attacks(king, empty) & west_attacks(straight, empty))
+            | (soea_attacks(king, empty) & nowe_attacks(diagonal, empty))
+            | (sout_attacks(king, empty) & nort_attacks(straight, empty))
+            | (sowe_attacks(king, empty) & noea_attacks(diagonal, empty))
+            | (west_attacks(king, empty) & east_attacks(straight, empty))
+            | (nowe_attacks(king, empty) & soea_attacks(diagonal, empty)))
+            & our_pieces;
+
+        let pinned_empty = empty | pinned;
+        let pinmask = PinMask {
+            h: (east_attacks(king, pinned_empty) | west_attacks(king, pinned_empty)) & !checkmask,
+            v: (nort_attacks(king, pinned_empty) | sout_attacks(king, pinned_empty)) & !checkmask,
+            d1: (noea_attacks(king, pinned_empty) | sowe_attacks(king, pinned_empty)) & !checkmask,
+            d2: (nowe_attacks(king, pinned_empty) | soea_attacks(king, pinned_empty)) & !checkmask,
+        };
+
+        if checkmask.is_empty() {
+            checkmask = ALL_BITS;
+        }
+        checkmask &= !our_pieces;
+
+        // enemy sliders see through our king, it can't step back along their line
+        let king_danger = self.attacks(S::THEM, empty | king);
+        Masks {
+            checkmask,
+            checkers,
+            pinmask,
+            pinned,
+            king_danger,
+            castling: self.legal_castling::<S>(king_danger),
+        }
+    }
+    /// The pieces of either color attacking `square` when exactly the squares in
+    /// `occupied` hold pieces; pass fewer to look through pieces, as exchanges do.
+    pub fn attackers_to(&self, square: Square, occupied: Bitboard) -> Bitboard {
+        let [white, black] = [Color::White, Color::Black].map(|color| self.pieces.bitboards(color));
+        let piece = |piece_type: PieceType| white[piece_type as usize] | black[piece_type as usize];
+        let straight = piece(PieceType::Queen) | piece(PieceType::Rook);
+        let diagonal = piece(PieceType::Queen) | piece(PieceType::Bishop);
+        // a pawn attacks the squares a pawn of the other color would attack from them
+        let pawns = (self.pawn_lookup[Color::Black as usize][square]
+            & white[PieceType::Pawn as usize])
+            | (self.pawn_lookup[Color::White as usize][square] & black[PieceType::Pawn as usize]);
+        let attackers = (rook_attacks(square.bitboard(), !occupied) & straight)
+            | (bishop_attacks(square.bitboard(), !occupied) & diagonal)
+            | (self.knight_lookup[square] & piece(PieceType::Knight))
+            | (self.king_lookup[square] & piece(PieceType::King))
+            | pawns;
+        attackers & occupied
+    }
+    /// Whether any piece of `by` attacks `square`.
+    pub fn is_square_attacked(&self, square: Square, by: Color) -> bool {
+        !(self.attackers_to(square, self.pieces.occupied()) & self.pieces.occupancy(by)).is_empty()
+    }
+    /// The sliders of either color lined up behind the pieces attacking `square`,
+    /// which join in once the pieces in front have taken.
+    pub fn xray_attackers(&self, square: Square) -> Bitboard {
+        let occupied = self.pieces.occupied();
+        let direct = self.attackers_to(square, occupied);
+        self.attackers_to(square, occupied & !direct) & !direct
+    }
+    // every square `color`'s pieces attack, with `empty` as the squares sliders pass
+    pub(crate) fn attacks(&self, color: Color, empty: Bitboard) -> Bitboard {
+        let [king, queen, rook, bishop, knight, pawn] = self.pieces.bitboards(color);
+        let mut attacks = rook_attacks(rook | queen, empty)
+            | bishop_attacks(bishop | queen, empty)
+            | self.king_lookup[king.lsb().expect("king")];
+        for square in pawn {
+            attacks |= self.pawn_lookup[color as usize][square];
+        }
+        for square in knight {
+            attacks |= self.knight_lookup[square];
+        }
+        attacks
+    }
+    // the rights say the king and rook haven't moved; castling also needs every
+    // square either of them crosses empty and the king's path, start included,
+    // unattacked
+    fn legal_castling<S: Side>(&self, king_danger: Bitboard) -> (bool, bool) {
+        let (queenside, kingside) = match S::COLOR {
+            Color::White => self.white_castling,
+            Color::Black => self.black_castling,
+        };
+        let [queenside_rook, kingside_rook] = self.castling_rooks[S::COLOR as usize];
+        (
+            queenside && self.can_castle::<S>(queenside_rook, false, king_danger),
+            kingside && self.can_castle::<S>(kingside_rook, true, king_danger),
+        )
+    }
+    fn can_castle<S: Side>(&self, rook: Square, kingside: bool, king_danger: Bitboard) -> bool {
+        let king = self.king_square[S::COLOR as usize];
+        let (king_file, rook_file) = if kingside { (6, 5) } else { (2, 3) };
+        if king.rank() != S::BACK_RANK
+            || (rook.file() > king.file()) != kingside
+            || !self
+                .pieces
+                .bitboard(S::COLOR, PieceType::Rook)
+                .contains(rook)
+        {
+            return false;
+        }
+        let king_path = rank_span(king, Square::new(king_file, S::BACK_RANK));
+        let castlers = king.bitboard() | rook.bitboard();
+        let crossed =
+            (king_path | rank_span(rook, Square::new(rook_file, S::BACK_RANK))) & !castlers;
+        // in Chess960 the rook can be what shields the king's path on the back rank
+        let [_, queen, their_rook, _, _, _] = self.pieces.bitboards(S::THEM);
+        let danger = king_danger | rook_attacks(queen | their_rook, self.empty | castlers);
+        (crossed & !self.empty).is_empty() && (danger & king_path).is_empty()
+    }
+    // safe steps, castling aside
+    fn king_targets<S: Side>(&self) -> Bitboard {
+        let king = self.king_square[S::COLOR as usize];
+        self.king_lookup[king] & !self.masks().king_danger & !self.pieces.occupancy(S::COLOR)
+    }
+    // the king taking the queenside and kingside rooks it may castle with
+    fn castling_moves<S: Side>(&self) -> impl Iterator<Item = Move> + '_ {
+        let (queenside, kingside) = self.masks().castling;
+        let rooks = self.castling_rooks[S::COLOR as usize];
+        [queenside, kingside]
+            .into_iter()
+            .zip(rooks)
+            .filter(|&(allowed, _)| allowed)
+            .map(|(_, rook)| Move {
+                from: self.king_square[S::COLOR as usize],
+                to: rook,
+                piece_color: S::COLOR,
+                promoted_piece: None,
+                castling: true,
+            })
+    }
+    fn king_moves<S: Side>(&self) -> Vec<Move> {
+        let mut moves: Vec<Move> = vec![];
+        let king = self.king_square[S::COLOR as usize];
+        for king_move in self.king_targets::<S>() {
+            moves.push(Move {
+                from: king,
+                to: king_move,
+                piece_color: S::COLOR,
+                promoted_piece: None,
+                castling: false,
+            });
+        }
+        moves.extend(self.castling_moves::<S>());
+        moves
+    }
+    fn knight_moves<S: Side>(&self) -> Vec<Move> {
+        let mut moves: Vec<Move> = vec![];
+        let masks = self.masks();
+        let our_pieces = self.pieces.occupancy(S::COLOR);
+        for knight in self.pieces.bitboard(S::COLOR, PieceType::Knight) {
+            if masks.pinned.contains(knight) {
+                continue;
+            }
+            let bb_moves = self.knight_lookup[knight] & !our_pieces & masks.checkmask;
+            for bb_move in bb_moves {
+                moves.push(Move {
+                    from: knight,
+                    to: bb_move,
+                    piece_color: S::COLOR,
+                    promoted_piece: None,
+                    castling: false,
+                });
+            }
+        }
+        moves
+    }
+    // the checkmask narrowed to the line a pinned piece shares with its king,
+    // None when the piece can't move along that line at all
+    fn pin_movemask<S: Side>(
+        &self,
+        square: Square,
+        straight: bool,
+        diagonal: bool,
+    ) -> Option<Bitboard> {
+        let masks = self.masks();
+        if !masks.pinned.contains(square) {
+            return Some(masks.checkmask);
+        }
+        let king = self.king_square[S::COLOR as usize];
+        let king_rank = king.rank() as i8;
+        let king_file = king.file() as i8;
+        let rank = square.rank() as i8;
+        let file = square.file() as i8;
+        let (line, pinmask) = if king_rank == rank {
+            (straight, masks.pinmask.h)
+        } else if king_file == file {
+            (straight, masks.pinmask.v)
+        } else if king_file - file == king_rank - rank {
+            (diagonal, masks.pinmask.d1)
+        } else if king_file - file == rank - king_rank {
+            (diagonal, masks.pinmask.d2)
+        } else {
+            panic!(
+                "pin? KR {} KF {} R {} F {}",
+                king_rank, king_file, rank, file
+            );
+        };
+        line.then_some(masks.checkmask & pinmask)
+    }
+    fn rook_moves<S: Side>(&self) -> Vec<Move> {
+        self.slider_moves::<S>(PieceType::Rook, true, false, rook_attacks)
+    }
+    fn bishop_moves<S: Side>(&self) -> Vec<Move> {
+        self.slider_moves::<S>(PieceType::Bishop, false, true, bishop_attacks)
+    }
+    fn queen_moves<S: Side>(&self) -> Vec<Move> {
+        self.slider_moves::<S>(PieceType::Queen, true, true, vision)
+    }
+    fn slider_moves<S: Side>(
+        &self,
+        piece_type: PieceType,
+        straight: bool,
+        diagonal: bool,
+        attacks: fn(Bitboard, Bitboard) -> Bitboard,
+    ) -> Vec<Move> {
+        let our_pieces = self.pieces.occupancy(S::COLOR);
+        let mut moves: Vec<Move> = vec![];
+        for current_piece in self.pieces.bitboard(S::COLOR, piece_type) {
+            let Some(movemask) = self.pin_movemask::<S>(current_piece, straight, diagonal) else {
+                continue;
+            };
+            let bb_moves = attacks(current_piece.bitboard(), self.empty) & !our_pieces & movemask;
+            for bb_move in bb_moves {
+                moves.push(Move {
+                    from: current_piece,
+                    to: bb_move,
+                    piece_color: S::COLOR,
+                    promoted_piece: None,
+                    castling: false,
+                });
+            }
+        }
+        moves
+    }
+    fn pawn_moves<S: Side>(&self) -> Vec<Move> {
+        let mut moves: Vec<Move> = vec![];
+        let pawn_lookup = &self.pawn_lookup[S::COLOR as usize];
+        for current_piece in self.pieces.bitboard(S::COLOR, PieceType::Pawn) {
+            // a pinned pawn keeps the pushes or captures along its pin line
+            let movemask = self
+                .pin_movemask::<S>(current_piece, true, true)
+                .expect("pawns move along every line");
+            let mut bb_moves = self.pawn_targets::<S>(current_piece) & movemask;
+            if let Some(sq) = self.en_passant {
+                if pawn_lookup[current_piece].contains(sq)
+                    && self.en_passant_is_legal::<S>(current_piece, sq)
+                {
+                    bb_moves |= sq.bitboard();
+                }
+            }
+            for bb_move in bb_moves {
+                push_pawn_moves(&mut moves, current_piece, bb_move, S::COLOR, S::LAST_RANK);
+            }
+        }
+        moves
+    }
+    // captures and pushes of the pawn on `from`, en passant aside
+    fn pawn_targets<S: Side>(&self, from: Square) -> Bitboard {
+        let mut bb = self.pawn_lookup[S::COLOR as usize][from] & self.pieces.occupancy(S::THEM);
+        let push = from.bitboard().shift(S::UP) & self.empty;
+        if !push.is_empty() {
+            bb |= push;
+            if from.rank() == S::PAWN_RANK {
+                bb |= push.shift(S::UP) & self.empty;
+            }
+        }
+        bb
+    }
+    // en passant takes two pawns off one rank or diagonal at once, so it is checked
+    // against the board it leaves behind instead of the pin and check masks
+    fn en_passant_is_legal<S: Side>(&self, from: Square, target: Square) -> bool {
+        let captured = target.bitboard().shift(S::DOWN);
+        let king = self.pieces.bitboard(S::COLOR, PieceType::King);
+        let their = self.pieces.bitboards(S::THEM);
+        let checkers = self.masks().checkers;
+        let straight = their[PieceType::Queen as usize] | their[PieceType::Rook as usize];
+        let diagonal = their[PieceType::Queen as usize] | their[PieceType::Bishop as usize];
+        let empty = (self.empty | from.bitboard() | captured) & !target.bitboard();
+        let sliders =
+            (rook_attacks(king, empty) & straight) | (bishop_attacks(king, empty) & diagonal);
+        // a knight or pawn check is only answered here by taking the pawn that gives it
+        sliders.is_empty() && (checkers & !(straight | diagonal) & !captured).is_empty()
+    }
+    /// Every legal move for the side to move.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.moves(self.active_color)
+    }
+    /// Whether `piece_move` is one of [`Position::legal_moves`], checked without
+    /// generating them. Any move may be passed, a corrupt one is just not legal.
+    pub fn is_legal(&self, piece_move: Move) -> bool {
+        match self.active_color {
+            Color::White => self.side_is_legal::<White>(piece_move),
+            Color::Black => self.side_is_legal::<Black>(piece_move),
+        }
+    }
+    // the same targets and masks as side_moves, for one move
+    fn side_is_legal<S: Side>(&self, piece_move: Move) -> bool {
+        let (from, to) = (piece_move.from, piece_move.to);
+        let Some(piece) = self.pieces.piece_on(from) else {
+            return false;
+        };
+        if piece_move.piece_color != S::COLOR || piece.color != S::COLOR {
+            return false;
+        }
+        let promotes = piece.piece_type == PieceType::Pawn && to.rank() == S::LAST_RANK;
+        if promotes != piece_move.promoted_piece.is_some() {
+            return false;
+        }
+        if piece_move.castling {
+            return self
+                .castling_moves::<S>()
+                .any(|castling| castling == piece_move);
+        }
+        if piece.piece_type == PieceType::King {
+            return self.king_targets::<S>().contains(to);
+        }
+        // in double check only the king moves
+        if self.masks().checkers.popcount() > 1 {
+            return false;
+        }
+        let occupied = from.bitboard();
+        let (straight, diagonal, targets) = match piece.piece_type {
+            PieceType::Queen => (true, true, vision(occupied, self.empty)),
+            PieceType::Rook => (true, false, rook_attacks(occupied, self.empty)),
+            PieceType::Bishop => (false, true, bishop_attacks(occupied, self.empty)),
+            PieceType::Knight => (false, false, self.knight_lookup[from]),
+            PieceType::Pawn => {
+                if self.en_passant == Some(to)
+                    && self.pawn_lookup[S::COLOR as usize][from].contains(to)
+                {
+                    return self.en_passant_is_legal::<S>(from, to);
+                }
+                (true, true, self.pawn_targets::<S>(from))
+            }
+            PieceType::King => unreachable!(),
+        };
+        self.pin_movemask::<S>(from, straight, diagonal)
+            .is_some_and(|movemask| {
+                (targets & !self.pieces.occupancy(S::COLOR) & movemask).contains(to)
+            })
+    }
+    /// Whether the side to move is in check.
+    pub fn in_check(&self) -> bool {
+        !self.masks().checkers.is_empty()
+    }
+    /// Whether the legal move `piece_move` checks the enemy king, directly or by
+    /// uncovering a slider, castling rook and en passant included.
+    pub fn gives_check(&self, piece_move: Move) -> bool {
+        let (from, to, color) = (piece_move.from, piece_move.to, piece_move.piece_color);
+        let piece = self
+            .pieces
+            .piece_on(from)
+            .expect("no piece to move")
+            .piece_type;
+        let mut ours = self.pieces.bitboards(color);
+        let placed = match piece_move.promoted_piece {
+            None => piece,
+            Some(promotion) => promotion.piece_type(),
+        };
+        let mut empty = (self.empty | from.bitboard()) & !to.bitboard();
+        if piece_move.castling {
+            // only the rook can give check, from its new square
+            let (king_to, rook_to) = castling_squares(piece_move);
+            let rooks = &mut ours[PieceType::Rook as usize];
+            *rooks = (*rooks & !to.bitboard()) | rook_to.bitboard();
+            empty = (self.empty | from.bitboard() | to.bitboard())
+                & !(king_to.bitboard() | rook_to.bitboard());
+        } else {
+            ours[piece as usize] &= !from.bitboard();
+            ours[placed as usize] |= to.bitboard();
+        }
+        if piece == PieceType::Pawn && self.en_passant == Some(to) {
+            empty |= Square::new(to.file(), from.rank()).bitboard();
+        }
+        // the squares checking the enemy king, seen from the king itself
+        let king = self.king_square[color.invert() as usize];
+        let [_, queen, rook, bishop, knight, pawn] = ours;
+        let checkers = (rook_attacks(king.bitboard(), empty) & (rook | queen))
+            | (bishop_attacks(king.bitboard(), empty) & (bishop | queen))
+            | (self.knight_lookup[king] & knight)
+            | (self.pawn_lookup[color.invert() as usize][king] & pawn);
+        !checkers.is_empty()
+    }
+    pub(crate) fn moves(&self, color: Color) -> Vec<Move> {
+        // the masks only exist for the side to move
+        debug_assert_eq!(color, self.active_color);
+        match color {
+            Color::White => self.side_moves::<White>(),
+            Color::Black => self.side_moves::<Black>(),
+        }
+    }
+    fn side_moves<S: Side>(&self) -> Vec<Move> {
+        if self.masks().checkers.popcount() > 1 {
+            self.king_moves::<S>()
+        } else {
+            let mut king_moves = self.king_moves::<S>();
+            let mut queen_moves = self.queen_moves::<S>();
+            let mut rook_moves = self.rook_moves::<S>();
+            let mut bishop_moves = self.bishop_moves::<S>();
+            let mut knight_moves = self.knight_moves::<S>();
+            let mut pawn_moves = self.pawn_moves::<S>();
+            king_moves.append(&mut queen_moves);
+            king_moves.append(&mut rook_moves);
+            king_moves.append(&mut bishop_moves);
+            king_moves.append(&mut knight_moves);
+            king_moves.append(&mut pawn_moves);
+            king_moves
+        }
+    }
+}
+
+// the squares from `a` to `b` on one rank, both included
+fn rank_span(a: Square, b: Square) -> Bitboard {
+    let (low, high) = (a.0.min(b.0), a.0.max(b.0));
+    Bitboard((u64::MAX >> (63 - (high - low))) << low)
+}
+// a pawn reaching the last rank becomes each of the four pieces in turn
+fn push_pawn_moves(moves: &mut Vec<Move>, from: Square, to: Square, color: Color, last_rank: u8) {
+    let promotions: &[Option<PromotionType>] = if to.rank() == last_rank {
+        &[
+            Some(PromotionType::Queen),
+            Some(PromotionType::Rook),
+            Some(PromotionType::Bishop),
+            Some(PromotionType::Knight),
+        ]
+    } else {
+        &[None]
+    };
+    for &promoted_piece in promotions {
+        moves.push(Move {
+            from,
+            to,
+            piece_color: color,
+            promoted_piece,
+            castling: false,
+        });
+    }
+}
+fn to_12x10(index: isize) -> isize {
+    index + 21 + 2 * (index / 8)
+}
+fn to_8x8(index: isize) -> isize {
+    (index - 21) - 2 * ((index - 21) / 10)
+}
+fn verify_index(index: isize) -> bool {
+    if !(21..=119).contains(&index) {
+        return false;
+    }
+    (index - 21) % 10 < 8
+}
+pub(crate) fn piece_lookup(
+    square: Square,
+    piece_type: PieceType,
+    piece_color: Option<Color>,
+) -> Bitboard {
+    let mut bitboard = Bitboard::EMPTY;
+    match piece_type {
+        PieceType::King => {
+            for piece_move in [-11, -10, -9, -1, 1, 9, 10, 11] {
+                let new = to_12x10(square.0 as isize) + piece_move;
+                if verify_index(new) && to_8x8(new) < 64 {
+                    bitboard |= Square(to_8x8(new) as u8).bitboard();
+                }
+            }
+        }
+        PieceType::Queen => {
+            'queen: for piece_move in [-11, -10, -9, -1, 1, 9, 10, 11] {
+                for multiplier in 1..8 {
+                    let new = to_12x10(square.0 as isize) + piece_move * multiplier;
+                    if verify_index(new) && to_8x8(new) < 64 {
+                        bitboard |= Square(to_8x8(new) as u8).bitboard();
+                    } else {
+                        continue 'queen;
+                    }
+                }
+            }
+        }
+        PieceType::Rook => {
+            'rook: for piece_move in [-10, -1, 1, 10] {
+                for multiplier in 1..8 {
+                    let new = to_12x10(square.0 as isize) + piece_move * multiplier;
+                    if verify_index(new) && to_8x8(new) < 64 {
+                        bitboard |= Square(to_8x8(new) as u8).bitboard();
+                    } else {
+                        continue 'rook;
+                    }
+                }
+            }
+        }
+        PieceType::Bishop => {
+            'bishop: for piece_move in [-11, -9, 9, 11] {
+                for multiplier in 1..8 {
+                    let new = to_12x10(square.0 as isize) + piece_move * multiplier;
+                    if verify_index(new) && to_8x8(new) < 64 {
+                        bitboard |= Square(to_8x8(new) as u8).bitboard();
+                    } else {
+                        continue 'bishop;
+                    }
+                }
+            }
+        }
+        PieceType::Knight => {
+            for piece_move in [-21, -19, -12, -8, 8, 12, 19, 21] {
+                let new = to_12x10(square.0 as isize) + piece_move;
+                if verify_index(new) && to_8x8(new) < 64 {
+                    bitboard |= Square(to_8x8(new) as u8).bitboard();
+                }
+            }
+        }
+        PieceType::Pawn => match piece_color {
+            Some(Color::White) => {
+                for piece_move in [11, 9] {
+                    let new = to_12x10(square.0 as isize) + piece_move;
+                    if verify_index(new) && to_8x8(new) < 64 {
+                        bitboard |= Square(to_8x8(new) as u8).bitboard();
+                    }
+                }
+            }
+            Some(Color::Black) => {
+                for piece_move in [-11, -9] {
+                    let new = to_12x10(square.0 as isize) + piece_move;
+                    if verify_index(new) && to_8x8(new) < 64 {
+                        bitboard |= Square(to_8x8(new) as u8).bitboard();
+                    }
+                }
+            }
+            None => panic!("must provide pawn color"),
+        },
+    };
+    bitboard
+}
+// the squares on the line between two aligned squares, both ends included
+pub(crate) fn generate_slide_lookup(key: Bitboard) -> Bitboard {
+    let mut bitboard = key;
+    let mut ends = key.into_iter();
+    let (Some(start), Some(end)) = (ends.next(), ends.next()) else {
+        panic!("need two squares");
+    };
+    let index1 = start.0 as i32;
+    let (rank1, file1) = (start.rank() as i32, start.file() as i32);
+    let (rank2, file2) = (end.rank() as i32, end.file() as i32);
+    let square = |index: i32| Square(index as u8).bitboard();
+    if rank1 - rank2 == file2 - file1 {
+        for file in file2..file1 {
+            bitboard |= square(index1 + (file - file2) * 7);
+        }
+    } else if rank1 - rank2 == file1 - file2 {
+        for file in file1..file2 {
+            bitboard |= square(index1 + (file - file1) * 9);
+        }
+    } else if rank1 == rank2 {
+        for file in file1..file2 {
+            bitboard |= square(index1 + file - file1);
+        }
+    } else if file1 == file2 {
+        for rank in rank1..rank2 {
+            bitboard |= square(index1 + (rank - rank1) * 8);
+        }
+    } else {
+        panic!("invalid direction");
+    }
+    bitboard
+}
@@ -0,0 +1,205 @@
+use std::sync::OnceLock;
+use std::{fs, io};
+
+use crate::position::Pieces;
+use crate::search;
+use crate::util::{Bitboard, Square};
+use crate::Color;
+
+// quantisation of the (768 -> N) x 2 -> 1 perspective network, as written by
+// trainers that export the "simple" raw i16 layout
+const QA: i32 = 255;
+const QB: i32 = 64;
+const SCALE: i32 = 400;
+// the largest score a network may return, short of the search's mate scores
+const MAX_EVAL: i64 = (search::MATE - search::MAX_PLY as i32 - 1) as i64;
+const INPUTS: usize = 768;
+
+// feature order inside each half of the input layer: pawn, knight, bishop, rook,
+// queen, king; indexed by PieceType (King, Queen, Rook, Bishop, Knight, Pawn)
+const FEATURE_PIECE: [usize; 6] = [5, 4, 3, 2, 1, 0];
+
+static NETWORK: OnceLock<Network> = OnceLock::new();
+
+pub struct Network {
+    hidden: usize,
+    feature_weights: Vec<i16>, // [feature][hidden]
+    feature_bias: Vec<i16>,
+    output_weights: Vec<i16>, // side to move half, then the other side
+    output_bias: i16,
+}
+
+impl Network {
+    // little-endian i16 words: feature weights, feature biases, output weights,
+    // output bias; the hidden size follows from the length and trailing padding
+    // is ignored
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Network> {
+        let words: Vec<i16> = bytes
+            .chunks_exact(2)
+            .map(|word| i16::from_le_bytes([word[0], word[1]]))
+            .collect();
+        let hidden = words.len().saturating_sub(1) / (INPUTS + 3);
+        if hidden == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "network file too short",
+            ));
+        }
+        let (feature_weights, rest) = words.split_at(INPUTS * hidden);
+        let (feature_bias, rest) = rest.split_at(hidden);
+        let (output_weights, rest) = rest.split_at(2 * hidden);
+        Ok(Network {
+            hidden,
+            feature_weights: feature_weights.to_vec(),
+            feature_bias: feature_bias.to_vec(),
+            output_weights: output_weights.to_vec(),
+            output_bias: rest[0],
+        })
+    }
+    pub fn load(path: &str) -> io::Result<Network> {
+        Network::from_bytes(&fs::read(path)?)
+    }
+    fn weights(&self, feature: usize) -> &[i16] {
+        &self.feature_weights[feature * self.hidden..(feature + 1) * self.hidden]
+    }
+}
+
+// installs the network used by every Position created afterwards; only the first
+// call has an effect
+pub fn set_network(network: Network) -> bool {
+    NETWORK.set(network).is_ok()
+}
+
+pub fn network() -> Option<&'static Network> {
+    NETWORK.get()
+}
+
+fn feature(perspective: Color, color: Color, piece: usize, square: Square) -> usize {
+    let (side, square) = match perspective {
+        Color::White => (color as usize, square),
+        Color::Black => (1 - color as usize, square.flip()),
+    };
+    side * 384 + FEATURE_PIECE[piece] * 64 + square.index()
+}
+
+// hidden layer pre-activations from both sides' points of view
+#[derive(Clone, Debug, PartialEq)]
+pub struct Accumulator {
+    white: Vec<i16>,
+    black: Vec<i16>,
+}
+
+impl Accumulator {
+    pub fn refresh(network: &Network, pieces: &Pieces) -> Accumulator {
+        let mut accumulator = Accumulator {
+            white: network.feature_bias.clone(),
+            black: network.feature_bias.clone(),
+        };
+        accumulator.apply_deltas(network, [[Bitboard::EMPTY; 6]; 2], bitboards(pieces));
+        accumulator
+    }
+    // applies the piece deltas between `before` and `after`, which covers captures,
+    // castling, promotions and en passant alike
+    pub fn update(&mut self, network: &Network, before: &Pieces, after: &Pieces) {
+        self.apply_deltas(network, bitboards(before), bitboards(after));
+    }
+    fn apply_deltas(
+        &mut self,
+        network: &Network,
+        before: [[Bitboard; 6]; 2],
+        after: [[Bitboard; 6]; 2],
+    ) {
+        for color in [Color::White, Color::Black] {
+            let (old, new) = (before[color as usize], after[color as usize]);
+            for piece in 0..6 {
+                for square in old[piece] & !new[piece] {
+                    self.toggle(network, color, piece, square, -1);
+                }
+                for square in new[piece] & !old[piece] {
+                    self.toggle(network, color, piece, square, 1);
+                }
+            }
+        }
+    }
+    fn toggle(&mut self, network: &Network, color: Color, piece: usize, square: Square, sign: i16) {
+        let white = network.weights(feature(Color::White, color, piece, square));
+        let black = network.weights(feature(Color::Black, color, piece, square));
+        for (value, weight) in self.white.iter_mut().zip(white) {
+            *value = value.wrapping_add(sign.wrapping_mul(*weight));
+        }
+        for (value, weight) in self.black.iter_mut().zip(black) {
+            *value = value.wrapping_add(sign.wrapping_mul(*weight));
+        }
+    }
+}
+
+fn bitboards(pieces: &Pieces) -> [[Bitboard; 6]; 2] {
+    [
+        pieces.bitboards(Color::White),
+        pieces.bitboards(Color::Black),
+    ]
+}
+
+// sum of clipped ReLU(x) * w, vectorised with AVX2 when the CPU has it
+pub fn crelu_dot(values: &[i16], weights: &[i16]) -> i32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: AVX2 support was just checked
+            return unsafe { crelu_dot_avx2(values, weights) };
+        }
+    }
+    crelu_dot_scalar(values, weights)
+}
+
+pub fn crelu_dot_scalar(values: &[i16], weights: &[i16]) -> i32 {
+    values
+        .iter()
+        .zip(weights)
+        .map(|(&value, &weight)| (value as i32).clamp(0, QA) * weight as i32)
+        .sum()
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn crelu_dot_avx2(values: &[i16], weights: &[i16]) -> i32 {
+    use std::arch::x86_64::*;
+
+    let chunks = values.len() / 16;
+    let zero = _mm256_setzero_si256();
+    let qa = _mm256_set1_epi16(QA as i16);
+    let mut sum = _mm256_setzero_si256();
+    for chunk in 0..chunks {
+        let value = _mm256_loadu_si256(values.as_ptr().add(chunk * 16) as *const __m256i);
+        let weight = _mm256_loadu_si256(weights.as_ptr().add(chunk * 16) as *const __m256i);
+        let clipped = _mm256_min_epi16(_mm256_max_epi16(value, zero), qa);
+        // widen to i32 before multiplying, QA * weight can overflow i16
+        let low = _mm256_mullo_epi32(
+            _mm256_cvtepi16_epi32(_mm256_castsi256_si128(clipped)),
+            _mm256_cvtepi16_epi32(_mm256_castsi256_si128(weight)),
+        );
+        let high = _mm256_mullo_epi32(
+            _mm256_cvtepi16_epi32(_mm256_extracti128_si256(clipped, 1)),
+            _mm256_cvtepi16_epi32(_mm256_extracti128_si256(weight, 1)),
+        );
+        sum = _mm256_add_epi32(sum, _mm256_add_epi32(low, high));
+    }
+    let mut lanes = [0i32; 8];
+    _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, sum);
+    let tail = chunks * 16;
+    lanes.iter().sum::<i32>() + crelu_dot_scalar(&values[tail..], &weights[tail..])
+}
+
+// centipawns from the side to move's point of view
+pub fn evaluate(network: &Network, accumulator: &Accumulator, side: Color) -> i32 {
+    let (us, them) = match side {
+        Color::White => (&accumulator.white, &accumulator.black),
+        Color::Black => (&accumulator.black, &accumulator.white),
+    };
+    let (own_weights, other_weights) = network.output_weights.split_at(network.hidden);
+    let sum = crelu_dot(us, own_weights) + crelu_dot(them, other_weights);
+    // clipped ReLU keeps the sum on the QA * QB scale of the output bias. The
+    // scaling is done in i64, a big hidden layer can push it past i32
+    let output = (sum as i64 + network.output_bias as i64) * SCALE as i64 / (QA * QB) as i64;
+    output.clamp(-MAX_EVAL, MAX_EVAL) as i32
+}
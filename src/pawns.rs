@@ -0,0 +1,134 @@
+use crate::evaluation::{
+    Score, StaticWeights, Weights, BACKWARD, CONNECTED, DOUBLED, ISOLATED, PASSED,
+};
+use crate::movegen::ALL_BITS;
+use crate::position::Pieces;
+use crate::sliding_pieces::{nort_attacks, sout_attacks};
+use crate::types::Invert;
+use crate::util::{Bitboard, Square};
+use crate::{Color, Direction, PieceType};
+
+pub const PAWN_TERMS: [&str; 5] = ["passed", "isolated", "doubled", "backward", "connected"];
+
+fn east_one(bb: Bitboard) -> Bitboard {
+    bb.shift(Direction::East)
+}
+fn west_one(bb: Bitboard) -> Bitboard {
+    bb.shift(Direction::West)
+}
+fn forward_one(bb: Bitboard, color: Color) -> Bitboard {
+    match color {
+        Color::White => bb.shift(Direction::North),
+        Color::Black => bb.shift(Direction::South),
+    }
+}
+// every square in front of the pawns, excluding their own squares
+fn front_span(bb: Bitboard, color: Color) -> Bitboard {
+    match color {
+        Color::White => nort_attacks(bb, ALL_BITS),
+        Color::Black => sout_attacks(bb, ALL_BITS),
+    }
+}
+fn file_fill(bb: Bitboard) -> Bitboard {
+    bb | nort_attacks(bb, ALL_BITS) | sout_attacks(bb, ALL_BITS)
+}
+pub fn pawn_attacks(bb: Bitboard, color: Color) -> Bitboard {
+    let forward = forward_one(bb, color);
+    east_one(forward) | west_one(forward)
+}
+pub fn relative_rank(square: Square, color: Color) -> usize {
+    match color {
+        Color::White => square.rank() as usize,
+        Color::Black => 7 - square.rank() as usize,
+    }
+}
+
+fn sum_by_rank(bb: Bitboard, color: Color, table: usize, weights: &mut impl Weights) -> Score {
+    let mut score = Score::default();
+    for square in bb {
+        score += weights.weight(color, table + relative_rank(square, color), 1);
+    }
+    score
+}
+
+// one Score per PAWN_TERMS entry
+fn pawn_terms(
+    own: Bitboard,
+    enemy: Bitboard,
+    color: Color,
+    weights: &mut impl Weights,
+) -> [Score; 5] {
+    let enemy_color = color.invert();
+    let enemy_front = front_span(enemy, enemy_color);
+    let enemy_attacks = pawn_attacks(enemy, enemy_color);
+    // only the front pawn of a doubled pair counts as passed
+    let behind_own = front_span(own, enemy_color);
+    let passed = own & !(enemy_front | east_one(enemy_front) | west_one(enemy_front) | behind_own);
+
+    let files = file_fill(own);
+    let isolated = own & !(east_one(files) | west_one(files));
+    // the rear pawn of a doubled pair is not penalised, only the ones in front of it
+    let doubled = own & front_span(own, color);
+
+    // the stop square is covered by an enemy pawn and no own pawn can ever defend it
+    let own_attack_span = front_span(pawn_attacks(own, color), color) | pawn_attacks(own, color);
+    let stops = forward_one(own, color);
+    let backward = own & forward_one(stops & enemy_attacks & !own_attack_span, enemy_color);
+
+    let supported = own & pawn_attacks(own, color);
+    let phalanx = own & (east_one(own) | west_one(own));
+    let connected = supported | phalanx;
+
+    [
+        sum_by_rank(passed, color, PASSED, weights),
+        weights.weight(color, ISOLATED, isolated.popcount() as i32),
+        weights.weight(color, DOUBLED, doubled.popcount() as i32),
+        weights.weight(color, BACKWARD, backward.popcount() as i32),
+        sum_by_rank(connected, color, CONNECTED, weights),
+    ]
+}
+
+// pawn structure scores for (white, black), per PAWN_TERMS entry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PawnEval {
+    pub white: [Score; 5],
+    pub black: [Score; 5],
+}
+
+pub fn evaluate(pieces: &Pieces) -> PawnEval {
+    evaluate_with(pieces, &mut StaticWeights)
+}
+
+pub fn evaluate_with(pieces: &Pieces, weights: &mut impl Weights) -> PawnEval {
+    let white_pawns = pieces.bitboard(Color::White, PieceType::Pawn);
+    let black_pawns = pieces.bitboard(Color::Black, PieceType::Pawn);
+    PawnEval {
+        white: pawn_terms(white_pawns, black_pawns, Color::White, weights),
+        black: pawn_terms(black_pawns, white_pawns, Color::Black, weights),
+    }
+}
+
+// cache keyed by the pawn-only Zobrist key, pawn structure changes far less often
+// than the rest of the position
+pub struct PawnTable {
+    entries: Vec<Option<(u64, PawnEval)>>,
+}
+
+impl PawnTable {
+    pub fn new(entries: usize) -> PawnTable {
+        PawnTable {
+            entries: vec![None; entries.max(1)],
+        }
+    }
+    pub fn get(&mut self, pawn_hash: u64, pieces: &Pieces) -> PawnEval {
+        let index = (pawn_hash % self.entries.len() as u64) as usize;
+        match self.entries[index] {
+            Some((key, eval)) if key == pawn_hash => eval,
+            _ => {
+                let eval = evaluate(pieces);
+                self.entries[index] = Some((pawn_hash, eval));
+                eval
+            }
+        }
+    }
+}
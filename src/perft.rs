@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::search::BENCH_POSITIONS;
+use crate::zobrist::splitmix64;
+use crate::{Move, Position};
+
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64, // position key ^ depth key ^ nodes, so torn writes never match
+    nodes: AtomicU64,
+}
+
+// node counts of subtrees already walked, keyed by position and remaining depth
+pub struct PerftTable {
+    slots: Vec<Slot>,
+}
+
+fn depth_key(hash: u64, depth: usize) -> u64 {
+    hash ^ splitmix64(depth as u64).1
+}
+
+impl PerftTable {
+    pub fn new(megabytes: usize) -> PerftTable {
+        let len = (megabytes * 1024 * 1024 / std::mem::size_of::<Slot>()).max(1);
+        PerftTable {
+            slots: (0..len).map(|_| Slot::default()).collect(),
+        }
+    }
+    fn slot(&self, key: u64) -> &Slot {
+        &self.slots[(key % self.slots.len() as u64) as usize]
+    }
+    fn probe(&self, key: u64) -> Option<u64> {
+        let slot = self.slot(key);
+        let nodes = slot.nodes.load(Ordering::Relaxed);
+        (slot.key.load(Ordering::Relaxed) ^ nodes == key).then_some(nodes)
+    }
+    fn store(&self, key: u64, nodes: u64) {
+        let slot = self.slot(key);
+        slot.key.store(key ^ nodes, Ordering::Relaxed);
+        slot.nodes.store(nodes, Ordering::Relaxed);
+    }
+}
+
+// leaf nodes `depth` plies below `state`, reusing and filling `table` when given
+pub fn perft(state: &Position, depth: usize, table: Option<&PerftTable>) -> u64 {
+    let Some(table) = table else {
+        return state.perft(depth);
+    };
+    if depth <= 1 {
+        return state.perft(depth);
+    }
+    let key = depth_key(state.hash, depth);
+    if let Some(nodes) = table.probe(key) {
+        return nodes;
+    }
+    let nodes = state
+        .moves(state.active_color)
+        .into_iter()
+        .map(|piece_move| perft(&state.apply_move(piece_move), depth - 1, Some(table)))
+        .sum();
+    table.store(key, nodes);
+    nodes
+}
+
+// nodes under each root move in generation order; the root moves are handed out
+// to `threads` workers one at a time so a large subtree doesn't hold up the rest
+pub fn divide(
+    state: &Position,
+    depth: usize,
+    threads: usize,
+    table: Option<&PerftTable>,
+) -> Vec<(Move, u64)> {
+    if depth == 0 {
+        return vec![];
+    }
+    let moves = state.moves(state.active_color);
+    let counts = Mutex::new(vec![0; moves.len()]);
+    let next = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, moves.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&piece_move) = moves.get(i) else {
+                    break;
+                };
+                let nodes = perft(&state.apply_move(piece_move), depth - 1, table);
+                counts.lock().unwrap()[i] = nodes;
+            });
+        }
+    });
+    moves
+        .into_iter()
+        .zip(counts.into_inner().unwrap())
+        .collect()
+}
+
+// single-threaded perft of every bench position, with the time each took, to
+// measure the move generator's speed
+pub fn bench(depth: usize) -> Vec<(&'static str, u64, Duration)> {
+    BENCH_POSITIONS
+        .into_iter()
+        .map(|fen| {
+            let state = Position::new(fen.to_owned());
+            let start = Instant::now();
+            let nodes = state.perft(depth);
+            (fen, nodes, start.elapsed())
+        })
+        .collect()
+}
+
+pub fn nodes_per_second(nodes: u64, elapsed: Duration) -> u64 {
+    (nodes as f64 / elapsed.as_secs_f64().max(1e-9)) as u64
+}
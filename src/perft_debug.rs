@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use crate::uci::parse_move;
+use crate::{perft, Position};
+
+// splits a perft count by root move, normally another engine
+pub trait Reference {
+    // counts below the position reached by playing `moves` from `fen`
+    fn divide(
+        &mut self,
+        fen: &str,
+        moves: &[String],
+        depth: usize,
+    ) -> io::Result<BTreeMap<String, u64>>;
+}
+
+// any engine that answers `go perft` the way Stockfish does: "<move>: <nodes>"
+// lines followed by "Nodes searched: <total>"
+pub struct UciEngine {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl UciEngine {
+    pub fn spawn(path: &str) -> io::Result<UciEngine> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("piped stdin");
+        let stdout = BufReader::new(child.stdout.take().expect("piped stdout"));
+        let mut engine = UciEngine {
+            child,
+            stdin,
+            stdout,
+        };
+        engine.send("uci")?;
+        while engine.read_line()? != "uciok" {}
+        Ok(engine)
+    }
+    fn send(&mut self, command: &str) -> io::Result<()> {
+        writeln!(self.stdin, "{}", command)?;
+        self.stdin.flush()
+    }
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "reference engine exited",
+            ));
+        }
+        Ok(line.trim().to_owned())
+    }
+}
+
+impl Reference for UciEngine {
+    fn divide(
+        &mut self,
+        fen: &str,
+        moves: &[String],
+        depth: usize,
+    ) -> io::Result<BTreeMap<String, u64>> {
+        let mut position = format!("position fen {}", fen);
+        if !moves.is_empty() {
+            position += &format!(" moves {}", moves.join(" "));
+        }
+        self.send(&position)?;
+        self.send(&format!("go perft {}", depth))?;
+        let mut counts = BTreeMap::new();
+        loop {
+            let line = self.read_line()?;
+            if line.starts_with("Nodes searched") {
+                return Ok(counts);
+            }
+            // other engines print info lines around the counts
+            if let Some((text, nodes)) = line.split_once(": ") {
+                if let Ok(nodes) = nodes.trim().parse() {
+                    counts.insert(text.to_owned(), nodes);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.child.wait();
+    }
+}
+
+// where our move generator and the reference first disagree
+#[derive(Debug)]
+pub struct Discrepancy {
+    pub moves: Vec<String>, // played from the root to get here
+    pub fen: String,
+    pub missing: Vec<String>, // only the reference generates these
+    pub extra: Vec<String>,   // only we do
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "moves: {}", self.moves.join(" "))?;
+        writeln!(f, "position: {}", self.fen)?;
+        writeln!(f, "missing: {}", self.missing.join(" "))?;
+        write!(f, "extra: {}", self.extra.join(" "))
+    }
+}
+
+// follows the first root move whose count differs, one ply at a time, until the
+// move lists themselves differ; None when every count agrees. `on_step` gets each
+// move followed with our count and the reference's below it. A FEN that doesn't
+// parse is an InvalidInput error
+pub fn find_discrepancy(
+    reference: &mut impl Reference,
+    fen: &str,
+    depth: usize,
+    mut on_step: impl FnMut(&str, u64, u64),
+) -> io::Result<Option<Discrepancy>> {
+    let mut state: Position = fen
+        .parse()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let mut moves: Vec<String> = vec![];
+    for depth in (1..=depth).rev() {
+        let ours: BTreeMap<String, u64> = perft::divide(&state, depth, 1, None)
+            .into_iter()
+            .map(|(piece_move, nodes)| (piece_move.display(), nodes))
+            .collect();
+        let theirs = reference.divide(fen, &moves, depth)?;
+        let only_in = |a: &BTreeMap<String, u64>, b: &BTreeMap<String, u64>| -> Vec<String> {
+            a.keys()
+                .filter(|text| !b.contains_key(*text))
+                .cloned()
+                .collect()
+        };
+        let missing = only_in(&theirs, &ours);
+        let extra = only_in(&ours, &theirs);
+        if !missing.is_empty() || !extra.is_empty() {
+            return Ok(Some(Discrepancy {
+                moves,
+                fen: state.fen(),
+                missing,
+                extra,
+            }));
+        }
+        let Some(text) = ours.keys().find(|text| ours[*text] != theirs[*text]) else {
+            return Ok(None);
+        };
+        on_step(text, ours[text], theirs[text]);
+        state =
+            state.apply_move(parse_move(&state, text, false).expect("move from our own divide"));
+        moves.push(text.clone());
+    }
+    Ok(None)
+}
@@ -0,0 +1,323 @@
+//! [`Position`], the board state everything else works on, and making moves on it.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::evaluation;
+use crate::movegen::{generate_slide_lookup, piece_lookup, Masks};
+#[cfg(feature = "nnue")]
+use crate::nnue;
+use crate::pawns;
+use crate::types::{Color, Invert, Move, Piece, PieceType};
+use crate::util::{Bitboard, Square};
+use crate::zobrist;
+
+// piece placement, as bitboards for movegen and as a mailbox for lookups by square
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Pieces {
+    bitboards: [[Bitboard; 6]; 2], // [color][piece type]
+    occupancy: [Bitboard; 2],      // [color]
+    mailbox: [Option<Piece>; 64],
+}
+impl Default for Pieces {
+    fn default() -> Pieces {
+        Pieces {
+            bitboards: [[Bitboard::EMPTY; 6]; 2],
+            occupancy: [Bitboard::EMPTY; 2],
+            mailbox: [None; 64],
+        }
+    }
+}
+impl Pieces {
+    pub(crate) fn bitboard(&self, color: Color, piece_type: PieceType) -> Bitboard {
+        self.bitboards[color as usize][piece_type as usize]
+    }
+    // bitboards of one color in PieceType order
+    pub(crate) fn bitboards(&self, color: Color) -> [Bitboard; 6] {
+        self.bitboards[color as usize]
+    }
+    pub(crate) fn occupancy(&self, color: Color) -> Bitboard {
+        self.occupancy[color as usize]
+    }
+    pub(crate) fn occupied(&self) -> Bitboard {
+        self.occupancy[0] | self.occupancy[1]
+    }
+    pub(crate) fn piece_on(&self, square: Square) -> Option<Piece> {
+        self.mailbox[square]
+    }
+    // the helpers below keep the bitboards, the occupancy and the mailbox in sync
+    pub(crate) fn put_piece(&mut self, piece: Piece, square: Square) {
+        let (color, piece_type) = (piece.color as usize, piece.piece_type as usize);
+        self.bitboards[color][piece_type] |= square.bitboard();
+        self.occupancy[color] |= square.bitboard();
+        self.mailbox[square] = Some(piece);
+    }
+    pub(crate) fn remove_piece(&mut self, square: Square) -> Option<Piece> {
+        let piece = self.mailbox[square].take()?;
+        let (color, piece_type) = (piece.color as usize, piece.piece_type as usize);
+        self.bitboards[color][piece_type] &= !square.bitboard();
+        self.occupancy[color] &= !square.bitboard();
+        Some(piece)
+    }
+    // onto an empty square, captures are removed first
+    pub(crate) fn move_piece(&mut self, from: Square, to: Square) {
+        let piece = self.remove_piece(from).expect("no piece to move");
+        self.put_piece(piece, to);
+    }
+    pub(crate) fn non_pawn_material(&self, color: Color) -> Bitboard {
+        let [_, queens, rooks, bishops, knights, _] = self.bitboards(color);
+        queens | rooks | bishops | knights
+    }
+}
+// where the king and rook land when castling, the g and f or c and d files in
+// Chess960 as well
+pub(crate) fn castling_squares(piece_move: Move) -> (Square, Square) {
+    let rook_file = if piece_move.to > piece_move.from {
+        5
+    } else {
+        3
+    };
+    (
+        piece_move.king_to(),
+        Square::new(rook_file, piece_move.from.rank()),
+    )
+}
+/// A chess position: the board, side to move, castling rights, en passant square
+/// and move clocks, along with the masks move generation needs.
+///
+/// Positions are immutable values; [`Position::apply_move`] returns the next one.
+#[derive(Clone)]
+pub struct Position {
+    pub(crate) pieces: Pieces,
+    pub(crate) empty: Bitboard,
+    pub(crate) white_castling: (bool, bool), //Queenside, Kingside (FEN)
+    pub(crate) black_castling: (bool, bool),
+    // [color][queenside, kingside] start squares of the castling rooks, only off
+    // the a and h files in Chess960
+    pub(crate) castling_rooks: [[Square; 2]; 2],
+    pub(crate) active_color: Color,
+    pub(crate) halfmoves: usize,
+    pub(crate) fullmoves: usize,
+    pub(crate) en_passant: Option<Square>,
+
+    pub(crate) king_lookup: Vec<Bitboard>,
+    pub(crate) knight_lookup: Vec<Bitboard>,
+    pub(crate) slide_lookup: HashMap<Bitboard, Bitboard>,
+
+    pub(crate) masks: OnceLock<Masks>, // side to move only, see Position::masks
+    pub(crate) king_square: [Square; 2],
+    pub(crate) pawn_lookup: [Vec<Bitboard>; 2], // [color] captures
+    pub(crate) hash: u64,
+    pub(crate) pawn_hash: u64,
+    #[cfg(feature = "nnue")]
+    pub(crate) accumulator: Option<nnue::Accumulator>,
+}
+
+impl Position {
+    // a position from its parsed FEN fields, see fen.rs
+    pub(crate) fn from_parts(
+        pieces: Pieces,
+        active_color: Color,
+        castling: [(bool, bool); 2],
+        castling_rooks: [[Square; 2]; 2],
+        en_passant: Option<Square>,
+        halfmoves: usize,
+        fullmoves: usize,
+    ) -> Position {
+        let mut king_lookup: Vec<Bitboard> = vec![];
+        let mut knight_lookup: Vec<Bitboard> = vec![];
+        let mut white_pawn_lookup: Vec<Bitboard> = vec![];
+        let mut black_pawn_lookup: Vec<Bitboard> = vec![];
+
+        let mut slide_lookup: HashMap<Bitboard, Bitboard> = HashMap::new();
+        for idx in (0..64).map(Square) {
+            //generate lookup tables
+            king_lookup.push(piece_lookup(idx, PieceType::King, None));
+            knight_lookup.push(piece_lookup(idx, PieceType::Knight, None));
+            black_pawn_lookup.push(piece_lookup(idx, PieceType::Pawn, Some(Color::Black)));
+            white_pawn_lookup.push(piece_lookup(idx, PieceType::Pawn, Some(Color::White)));
+            // the line between every pair of squares a queen move apart
+            for slide_square in piece_lookup(idx, PieceType::Queen, None) {
+                let key = idx.bitboard() | slide_square.bitboard();
+                slide_lookup.insert(key, generate_slide_lookup(key));
+            }
+        }
+        let [white_castling, black_castling] = castling;
+        let mut gamestate = Position {
+            pieces,
+            empty: Bitboard::EMPTY,
+            white_castling,
+            black_castling,
+            castling_rooks,
+            active_color,
+            halfmoves,
+            fullmoves,
+            en_passant,
+            king_lookup,
+            knight_lookup,
+            pawn_lookup: [white_pawn_lookup, black_pawn_lookup],
+            slide_lookup,
+            king_square: [Square(0); 2],
+            masks: OnceLock::new(),
+            hash: 0,
+            pawn_hash: zobrist::pawn_hash(&pieces),
+            #[cfg(feature = "nnue")]
+            accumulator: nnue::network()
+                .map(|network| nnue::Accumulator::refresh(network, &pieces)),
+        };
+        gamestate.update_masks();
+        gamestate.hash = zobrist::hash(&gamestate);
+        gamestate
+    }
+    /// The position after `piece_move`, which must be one of [`Position::legal_moves`].
+    ///
+    /// Legality isn't checked here, that is the job of the move generator.
+    pub fn apply_move(&self, piece_move: Move) -> Position {
+        let mut new_gamestate = self.clone();
+        let (from, to, color) = (piece_move.from, piece_move.to, piece_move.piece_color);
+        let piece = self
+            .pieces
+            .piece_on(from)
+            .expect("no piece to move")
+            .piece_type;
+        let mut captured = match piece_move.castling {
+            true => None,
+            false => self.pieces.piece_on(to).map(|piece| (piece.piece_type, to)),
+        };
+        if piece == PieceType::Pawn && Some(to) == self.en_passant {
+            let square = match color {
+                Color::White => Square(to.0 - 8),
+                Color::Black => Square(to.0 + 8),
+            };
+            captured = Some((PieceType::Pawn, square));
+        }
+        let pieces = &mut new_gamestate.pieces;
+        if let Some((captured, square)) = captured {
+            if captured == PieceType::King {
+                panic!("illegal move");
+            }
+            pieces.remove_piece(square);
+        }
+        let placed = Piece {
+            color,
+            piece_type: match piece_move.promoted_piece {
+                None => piece,
+                Some(promotion) => promotion.piece_type(),
+            },
+        };
+        // castling is encoded as the king taking its rook; the rook is lifted
+        // first as it may stand on the king's destination
+        if piece_move.castling {
+            let (king_to, rook_to) = castling_squares(piece_move);
+            let rook = pieces.remove_piece(to).expect("no rook to castle with");
+            pieces.move_piece(from, king_to);
+            pieces.put_piece(rook, rook_to);
+        } else {
+            pieces.remove_piece(from);
+            pieces.put_piece(placed, to);
+        }
+        // moving the king loses both rights, moving or taking a castling rook its own
+        for (color_index, rights) in [
+            &mut new_gamestate.white_castling,
+            &mut new_gamestate.black_castling,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let [queenside, kingside] = self.castling_rooks[color_index];
+            if piece == PieceType::King && color as usize == color_index {
+                *rights = (false, false);
+            }
+            if [from, to].contains(&queenside) {
+                rights.0 = false;
+            }
+            if [from, to].contains(&kingside) {
+                rights.1 = false;
+            }
+        }
+        // the en passant square is only kept when an enemy pawn could take on it
+        new_gamestate.en_passant = None;
+        if piece == PieceType::Pawn && from.0.abs_diff(to.0) == 16 {
+            let skipped = Square((from.0 + to.0) / 2);
+            let enemy_pawns = self.pieces.bitboard(color.invert(), PieceType::Pawn);
+            if !(pawns::pawn_attacks(skipped.bitboard(), color) & enemy_pawns).is_empty() {
+                new_gamestate.en_passant = Some(skipped);
+            }
+        }
+        new_gamestate.halfmoves = if piece == PieceType::Pawn || captured.is_some() {
+            0
+        } else {
+            self.halfmoves + 1
+        };
+        if color == Color::Black {
+            new_gamestate.fullmoves += 1;
+        }
+        new_gamestate.active_color = new_gamestate.active_color.invert();
+        new_gamestate.update_masks();
+        new_gamestate.hash = zobrist::hash(&new_gamestate);
+        new_gamestate.pawn_hash = zobrist::pawn_hash(&new_gamestate.pieces);
+        #[cfg(feature = "nnue")]
+        if let (Some(network), Some(accumulator)) =
+            (nnue::network(), new_gamestate.accumulator.as_mut())
+        {
+            accumulator.update(network, &self.pieces, &new_gamestate.pieces);
+        }
+        new_gamestate
+    }
+    // passes the turn; the masks belonged to the other side
+    pub(crate) fn apply_null_move(&self) -> Position {
+        let mut new_gamestate = self.clone();
+        new_gamestate.en_passant = None;
+        new_gamestate.active_color = new_gamestate.active_color.invert();
+        new_gamestate.masks = OnceLock::new();
+        new_gamestate.hash = zobrist::hash(&new_gamestate);
+        new_gamestate
+    }
+    /// Counts the leaf nodes of the legal move tree `depth` plies deep.
+    pub fn perft(&self, depth: usize) -> u64 {
+        let mut nodes = 0;
+        if depth == 0 {
+            return 1;
+        }
+        for piece_move in self.legal_moves() {
+            let new_gamestate = self.apply_move(piece_move);
+            nodes += new_gamestate.perft(depth - 1);
+        }
+        nodes
+    }
+    pub(crate) fn static_eval(&self) -> i32 {
+        evaluation::evaluate(self)
+    }
+    /// The side to move.
+    pub fn side_to_move(&self) -> Color {
+        self.active_color
+    }
+    /// The piece standing on `square`, if any.
+    pub fn piece_on(&self, square: Square) -> Option<Piece> {
+        self.pieces.piece_on(square)
+    }
+    /// The square a pawn can capture en passant on, only set when such a capture is
+    /// possible.
+    pub fn en_passant(&self) -> Option<Square> {
+        self.en_passant
+    }
+    /// Plies since the last capture or pawn move.
+    pub fn halfmoves(&self) -> usize {
+        self.halfmoves
+    }
+    /// Starts at 1 and goes up after each Black move.
+    pub fn fullmoves(&self) -> usize {
+        self.fullmoves
+    }
+    /// Zobrist key, equal for positions that repeat.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// The standard starting position.
+impl Default for Position {
+    fn default() -> Position {
+        Position::new(crate::fen::START_FEN.to_owned())
+    }
+}
@@ -0,0 +1,234 @@
+use crate::movegen::{generate_slide_lookup, piece_lookup};
+use crate::position::castling_squares;
+use crate::types::Invert;
+use crate::util::{Bitboard, Square};
+#[cfg(any(test, feature = "reference"))]
+use crate::zobrist::splitmix64;
+use crate::{Color, Move, Piece, PieceType, Position, PromotionType};
+
+// a slow move generator meant to be read rather than run: every move the piece
+// rules allow, dropped when it leaves the king attacked. it only uses the lookup
+// tables and the mailbox, none of the check, pin or danger masks
+
+fn between(a: Square, b: Square) -> Bitboard {
+    if a == b {
+        return Bitboard::EMPTY;
+    }
+    let ends = a.bitboard() | b.bitboard();
+    generate_slide_lookup(ends) & !ends
+}
+
+fn occupied(state: &Position) -> Bitboard {
+    state.pieces.occupied()
+}
+
+fn attacks(piece: PieceType, color: Color, from: Square, to: Square, occupied: Bitboard) -> bool {
+    let color = (piece == PieceType::Pawn).then_some(color);
+    if !piece_lookup(from, piece, color).contains(to) {
+        return false;
+    }
+    match piece {
+        PieceType::Queen | PieceType::Rook | PieceType::Bishop => {
+            (between(from, to) & occupied).is_empty()
+        }
+        _ => true,
+    }
+}
+
+pub fn is_square_attacked(state: &Position, square: Square, by: Color) -> bool {
+    (0..64).map(Square).any(|from| {
+        state.pieces.piece_on(from).is_some_and(|piece| {
+            piece.color == by && attacks(piece.piece_type, by, from, square, occupied(state))
+        })
+    })
+}
+
+pub fn king_square(state: &Position, color: Color) -> Square {
+    state
+        .pieces
+        .bitboard(color, PieceType::King)
+        .lsb()
+        .expect("no king")
+}
+
+// the king taking a rook it still has the right to castle with, when every square
+// the two cross is free and the king's path isn't attacked
+fn castling_moves(state: &Position, color: Color) -> Vec<Move> {
+    let (queenside, kingside) = match color {
+        Color::White => state.white_castling,
+        Color::Black => state.black_castling,
+    };
+    let king = king_square(state, color);
+    let rook_piece = Some(Piece {
+        color,
+        piece_type: PieceType::Rook,
+    });
+    let mut moves = vec![];
+    for (right, side) in [(queenside, 0), (kingside, 1)] {
+        let rook = state.castling_rooks[color as usize][side];
+        let piece_move = Move {
+            from: king,
+            to: rook,
+            promoted_piece: None,
+            piece_color: color,
+            castling: true,
+        };
+        let (king_to, rook_to) = castling_squares(piece_move);
+        let on_its_side = (rook.file() > king.file()) == (side == 1);
+        if !right
+            || !on_its_side
+            || king.rank() != rook.rank()
+            || state.pieces.piece_on(rook) != rook_piece
+        {
+            continue;
+        }
+        let crossed = between(king, king_to)
+            | between(rook, rook_to)
+            | king_to.bitboard()
+            | rook_to.bitboard();
+        let path = between(king, king_to) | king.bitboard() | king_to.bitboard();
+        let free = (crossed & occupied(state) & !(king.bitboard() | rook.bitboard())).is_empty();
+        if free
+            && path
+                .into_iter()
+                .all(|square| !is_square_attacked(state, square, color.invert()))
+        {
+            moves.push(piece_move);
+        }
+    }
+    moves
+}
+
+// moves that follow the piece rules, whether or not they leave the king attacked
+pub fn pseudo_legal_moves(state: &Position) -> Vec<Move> {
+    let color = state.active_color;
+    let occupied = occupied(state);
+    let (forward, start_rank, last_rank) = match color {
+        Color::White => (8i8, 1, 7),
+        Color::Black => (-8, 6, 0),
+    };
+    let mut moves = vec![];
+    let squares = || (0..64).map(Square);
+    for from in state.pieces.occupancy(color) {
+        let piece = state
+            .pieces
+            .piece_on(from)
+            .expect("occupied square without a piece")
+            .piece_type;
+        for to in squares() {
+            if state.pieces.occupancy(color).contains(to) {
+                continue;
+            }
+            let empty = !occupied.contains(to);
+            let step = to.0 as i8 - from.0 as i8;
+            let allowed = match piece {
+                PieceType::Pawn => {
+                    (step == forward && empty)
+                        || (step == 2 * forward
+                            && from.rank() == start_rank
+                            && empty
+                            && !occupied.contains(Square((from.0 as i8 + forward) as u8)))
+                        || (attacks(piece, color, from, to, occupied)
+                            && (!empty || state.en_passant == Some(to)))
+                }
+                _ => attacks(piece, color, from, to, occupied),
+            };
+            if !allowed {
+                continue;
+            }
+            let promotions = if piece == PieceType::Pawn && to.rank() == last_rank {
+                vec![
+                    Some(PromotionType::Queen),
+                    Some(PromotionType::Rook),
+                    Some(PromotionType::Bishop),
+                    Some(PromotionType::Knight),
+                ]
+            } else {
+                vec![None]
+            };
+            for promoted_piece in promotions {
+                moves.push(Move {
+                    from,
+                    to,
+                    promoted_piece,
+                    piece_color: color,
+                    castling: false,
+                });
+            }
+        }
+    }
+    moves.extend(castling_moves(state, color));
+    moves
+}
+
+pub fn legal_moves(state: &Position) -> Vec<Move> {
+    let color = state.active_color;
+    pseudo_legal_moves(state)
+        .into_iter()
+        .filter(|&piece_move| {
+            let after = state.apply_move(piece_move);
+            !is_square_attacked(&after, king_square(&after, color), color.invert())
+        })
+        .collect()
+}
+
+// Err names the moves only one of the generators found
+pub fn compare(state: &Position) -> Result<(), String> {
+    let mut generated: Vec<String> = state
+        .moves(state.active_color)
+        .iter()
+        .map(|piece_move| piece_move.uci(true))
+        .collect();
+    let mut expected: Vec<String> = legal_moves(state)
+        .iter()
+        .map(|piece_move| piece_move.uci(true))
+        .collect();
+    generated.sort();
+    expected.sort();
+    if generated != expected {
+        let missing: Vec<&String> = expected.iter().filter(|m| !generated.contains(m)).collect();
+        let extra: Vec<&String> = generated.iter().filter(|m| !expected.contains(m)).collect();
+        return Err(format!(
+            "{}: missing {:?}, extra {:?}",
+            state.fen(),
+            missing,
+            extra
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(any(test, feature = "reference"))]
+const MAX_PLIES: usize = 200;
+
+// compares both generators on `positions` positions from random games, started
+// from the perft suite so castling, promotions and en passant come up early
+#[cfg(any(test, feature = "reference"))]
+pub fn differential(positions: u64, seed: u64) -> Result<u64, String> {
+    let starts: Vec<&str> = include_str!("perft_suite.epd")
+        .lines()
+        .filter_map(|line| line.split(';').next())
+        .map(str::trim)
+        .filter(|fen| !fen.is_empty())
+        .collect();
+    let mut rng = seed;
+    let mut random = || {
+        let value;
+        (rng, value) = splitmix64(rng);
+        value as usize
+    };
+    let mut checked = 0;
+    while checked < positions {
+        let mut state = Position::new(starts[random() % starts.len()].to_owned());
+        for _ in 0..MAX_PLIES {
+            compare(&state)?;
+            checked += 1;
+            let moves = state.moves(state.active_color);
+            if moves.is_empty() || checked == positions {
+                break;
+            }
+            state = state.apply_move(moves[random() % moves.len()]);
+        }
+    }
+    Ok(checked)
+}
@@ -0,0 +1,113 @@
+//! Standard Algebraic Notation, the move format of PGN files and most GUIs.
+//!
+//! ```
+//! use bitboard_generator::{san, Position};
+//!
+//! let position = Position::default();
+//! let knight = san::parse(&position, "Nf3").unwrap();
+//! assert_eq!(knight.display(), "g1f3");
+//! assert_eq!(san::format(&position, knight), "Nf3");
+//! ```
+
+use crate::position::Position;
+use crate::types::{Move, PieceType, PromotionType};
+use crate::util::Square;
+
+fn letter(piece_type: PieceType) -> &'static str {
+    match piece_type {
+        PieceType::King => "K",
+        PieceType::Queen => "Q",
+        PieceType::Rook => "R",
+        PieceType::Bishop => "B",
+        PieceType::Knight => "N",
+        PieceType::Pawn => "",
+    }
+}
+
+fn promotion_letter(promotion: PromotionType) -> &'static str {
+    match promotion {
+        PromotionType::Queen => "Q",
+        PromotionType::Rook => "R",
+        PromotionType::Bishop => "B",
+        PromotionType::Knight => "N",
+    }
+}
+
+/// `piece_move`, which must be legal in `position`, in SAN, ending in `+` or `#`
+/// when it gives check or mate.
+pub fn format(position: &Position, piece_move: Move) -> String {
+    let (from, to) = (piece_move.from, piece_move.to);
+    let piece = position
+        .piece_on(from)
+        .expect("move from an empty square")
+        .piece_type;
+    let mut san = if piece_move.castling {
+        match to.file() > from.file() {
+            true => "O-O".to_owned(),
+            false => "O-O-O".to_owned(),
+        }
+    } else {
+        let capture = position.piece_on(to).is_some()
+            || (piece == PieceType::Pawn && position.en_passant == Some(to));
+        let mut san = letter(piece).to_owned();
+        if piece == PieceType::Pawn && capture {
+            san.push_str(&from.to_string()[..1]);
+        } else if piece != PieceType::Pawn {
+            san.push_str(&disambiguation(position, piece, from, to));
+        }
+        if capture {
+            san.push('x');
+        }
+        san.push_str(&to.to_string());
+        if let Some(promotion) = piece_move.promoted_piece {
+            san.push('=');
+            san.push_str(promotion_letter(promotion));
+        }
+        san
+    };
+    let after = position.apply_move(piece_move);
+    if after.in_check() {
+        san.push(match after.legal_moves().is_empty() {
+            true => '#',
+            false => '+',
+        });
+    }
+    san
+}
+
+// the file, rank or whole square of `from` when another piece of the same kind
+// can also move to `to`
+fn disambiguation(position: &Position, piece: PieceType, from: Square, to: Square) -> String {
+    let rivals: Vec<Square> = position
+        .legal_moves()
+        .into_iter()
+        .filter(|other| other.to == to && other.from != from)
+        .filter(|other| position.piece_on(other.from).map(|p| p.piece_type) == Some(piece))
+        .map(|other| other.from)
+        .collect();
+    let square = from.to_string();
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|rival| rival.file() != from.file()) {
+        square[..1].to_owned()
+    } else if rivals.iter().all(|rival| rival.rank() != from.rank()) {
+        square[1..].to_owned()
+    } else {
+        square
+    }
+}
+
+// check marks and annotations are optional when parsing, and castling may use zeros
+fn normalize(san: &str) -> String {
+    san.trim_end_matches(['+', '#', '!', '?']).replace('0', "O")
+}
+
+/// The legal move `text` names in `position`.
+pub fn parse(position: &Position, text: &str) -> Result<Move, String> {
+    let wanted = normalize(text.trim());
+    position
+        .legal_moves()
+        .into_iter()
+        .find(|&piece_move| normalize(&format(position, piece_move)) == wanted)
+        .ok_or_else(|| format!("{:?} isn't a legal move in {}", text, position.fen()))
+}
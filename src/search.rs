@@ -0,0 +1,744 @@
+//! Alpha-beta search with iterative deepening, a shared transposition table and
+//! Lazy SMP threads.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crate::evaluation::Evaluator;
+use crate::move_ordering::{is_quiet, MoveOrdering};
+use crate::time_management::{TimeManager, CHECK_INTERVAL};
+use crate::transposition::{score_from_tt, score_to_tt, Bound, TranspositionTable};
+use crate::{Move, Position};
+
+/// The result of a search.
+#[non_exhaustive]
+pub struct Evaluation {
+    /// The move to play.
+    pub best_move: Move,
+    /// Centipawns from the side to move's point of view, see [`is_mate_score`].
+    pub score: i32,
+    /// The expected line, starting with `best_move`.
+    pub pv: Vec<Move>,
+}
+
+/// The deepest ply the search reaches from the root, extensions included.
+pub const MAX_PLY: usize = 64;
+/// A bound beyond every score the search returns.
+pub const INFINITY: i32 = 32_000;
+/// The score of being mated on the board; a mate `n` plies from the root scores
+/// `MATE - n` for the winning side.
+pub const MATE: i32 = 31_000;
+
+const ASPIRATION_DEPTH: usize = 4;
+const ASPIRATION_WINDOW: i32 = 25;
+// Lazy SMP helper `id` skips the depths where ((depth + phase) / size) is odd,
+// so the threads spread over different iterations
+const SKIP_SIZE: [usize; 20] = [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4, 4, 4, 4];
+const SKIP_PHASE: [usize; 20] = [0, 1, 0, 1, 2, 3, 0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5, 6, 7];
+pub(crate) const DEFAULT_HASH_MB: usize = 16;
+
+pub(crate) const BENCH_POSITIONS: [&str; 5] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P3/2NP1N2/PPP1QPPP/R4RK1 w - - 0 10",
+    "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N2N2/PP2BPPP/R2QKB1R w - - 0 8",
+    "2r3k1/pp3ppp/2n1b3/3p4/3P4/2N1B3/PP3PPP/2R3K1 w - - 0 20",
+];
+
+/// Selective search switches and margins, every technique can be turned off on its own.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct SearchParams {
+    /// Null-move pruning: pass the turn and cut off if a reduced search still
+    /// fails high.
+    pub null_move: bool,
+    /// The remaining depth a null move needs.
+    pub null_move_min_depth: usize,
+    /// How many plies the null-move search is reduced by, plus one per 6 plies
+    /// of remaining depth.
+    pub null_move_reduction: usize,
+    /// Late move reductions: search quiet moves late in the list shallower first.
+    pub lmr: bool,
+    /// The remaining depth a reduction needs.
+    pub lmr_min_depth: usize,
+    /// How many moves are searched at full depth before reductions start.
+    pub lmr_min_moves: usize,
+    /// A reduction is `lmr_base + ln(depth) * ln(move number) / lmr_divisor` plies.
+    pub lmr_base: f64,
+    /// See [`lmr_base`](Self::lmr_base).
+    pub lmr_divisor: f64,
+    /// Reverse futility pruning: return the static evaluation when it beats beta
+    /// by a margin.
+    pub reverse_futility: bool,
+    /// The deepest remaining depth reverse futility pruning applies at.
+    pub reverse_futility_depth: usize,
+    /// Its margin per ply of remaining depth, in centipawns.
+    pub reverse_futility_margin: i32,
+    /// Futility pruning: skip quiet moves when the static evaluation plus a margin
+    /// can't reach alpha.
+    pub futility: bool,
+    /// The deepest remaining depth futility pruning applies at.
+    pub futility_depth: usize,
+    /// Its margin per ply of remaining depth, in centipawns.
+    pub futility_margin: i32,
+    /// Late move pruning: skip the remaining quiet moves once enough have been
+    /// searched.
+    pub late_move_pruning: bool,
+    /// The deepest remaining depth late move pruning applies at.
+    pub late_move_pruning_depth: usize,
+    /// Quiet moves searched before pruning starts, plus the square of the
+    /// remaining depth.
+    pub late_move_pruning_base: usize,
+    /// Search one ply deeper when the side to move is in check.
+    pub check_extensions: bool,
+    /// Search the TT move one ply deeper when a reduced search shows every other
+    /// move falls clearly short of it.
+    pub singular_extensions: bool,
+    /// The remaining depth a singular extension needs.
+    pub singular_min_depth: usize,
+    /// How many extensions one line from the root may get.
+    pub max_extensions: usize,
+}
+
+impl Default for SearchParams {
+    fn default() -> SearchParams {
+        SearchParams {
+            null_move: true,
+            null_move_min_depth: 3,
+            null_move_reduction: 3,
+            lmr: true,
+            lmr_min_depth: 3,
+            lmr_min_moves: 3,
+            lmr_base: 0.75,
+            lmr_divisor: 2.25,
+            reverse_futility: true,
+            reverse_futility_depth: 6,
+            reverse_futility_margin: 80,
+            futility: true,
+            futility_depth: 3,
+            futility_margin: 120,
+            late_move_pruning: true,
+            late_move_pruning_depth: 3,
+            late_move_pruning_base: 4,
+            check_extensions: true,
+            singular_extensions: true,
+            singular_min_depth: 6,
+            max_extensions: 16,
+        }
+    }
+}
+
+impl SearchParams {
+    /// Plain alpha-beta without pruning or reductions, the baseline when A/B
+    /// testing them. Extensions stay on.
+    pub fn full_width() -> SearchParams {
+        SearchParams {
+            null_move: false,
+            lmr: false,
+            reverse_futility: false,
+            futility: false,
+            late_move_pruning: false,
+            ..SearchParams::default()
+        }
+    }
+}
+
+/// Whether `score` is a forced mate rather than an evaluation, see [`MATE`].
+pub fn is_mate_score(score: i32) -> bool {
+    score.abs() >= MATE - MAX_PLY as i32
+}
+
+// UCI `info` line for a finished iteration, mate scores are reported in moves
+pub(crate) fn info_line(
+    depth: usize,
+    score: i32,
+    nodes: u64,
+    pv: &[Move],
+    chess960: bool,
+) -> String {
+    let score = if is_mate_score(score) {
+        let moves = (MATE - score.abs() + 1) / 2;
+        format!("mate {}", if score > 0 { moves } else { -moves })
+    } else {
+        format!("cp {}", score)
+    };
+    let pv: Vec<String> = pv
+        .iter()
+        .map(|piece_move| piece_move.uci(chess960))
+        .collect();
+    format!(
+        "info depth {} score {} nodes {} pv {}",
+        depth,
+        score,
+        nodes,
+        pv.join(" ")
+    )
+}
+
+/// Searches positions, keeping its transposition table and move ordering
+/// history between searches.
+pub struct Searcher {
+    /// The nodes visited by the last search.
+    pub nodes: u64,
+    pub(crate) ordering: MoveOrdering,
+    pub(crate) use_ordering: bool,
+    /// Print a UCI `info` line after every completed iteration.
+    pub print_info: bool,
+    /// Print castling as the king taking its rook, for `UCI_Chess960`.
+    pub chess960: bool,
+    pub(crate) tt: Arc<TranspositionTable>,
+    pub(crate) thread_id: usize, // 0 for the main thread
+    pub(crate) completed_depth: usize,
+    /// Stop once this many nodes have been searched.
+    pub node_limit: Option<u64>,
+    /// The [`TimeManager`] limits of [`iterate`](Self::iterate), `None` to search
+    /// without a clock.
+    pub time: Option<TimeManager>,
+    /// Raise it from another thread, as UCI `stop` does, to end the search early.
+    pub stop: Arc<AtomicBool>,
+    // the clock is ignored while raised, UCI `ponderhit` lowers it
+    pub(crate) pondering: Arc<AtomicBool>,
+    // set once a limit is hit, everything searched after that is thrown away;
+    // limits only apply once there is a move to fall back on
+    stopped: bool,
+    can_stop: bool,
+    evaluator: Evaluator,
+    params: SearchParams,
+    lmr_table: Box<[[usize; 64]; MAX_PLY]>, // [depth][move number]
+    // triangular PV table: row `ply` holds the line found from that ply onwards
+    pv_table: Box<[[Option<Move>; MAX_PLY + 1]; MAX_PLY + 1]>,
+    pv_length: [usize; MAX_PLY + 1],
+    // per ply search stack: move skipped by a singular verification search,
+    // and extensions spent on the line so far
+    excluded: [Option<Move>; MAX_PLY + 1],
+    extensions: [usize; MAX_PLY + 1],
+}
+
+impl Default for Searcher {
+    fn default() -> Searcher {
+        Searcher::new()
+    }
+}
+
+impl Searcher {
+    /// A searcher with the default parameters and a 16 MB transposition table.
+    pub fn new() -> Searcher {
+        Searcher::with_params(SearchParams::default())
+    }
+    /// A searcher with `params` and a 16 MB transposition table.
+    pub fn with_params(params: SearchParams) -> Searcher {
+        Searcher::with_tt(params, Arc::new(TranspositionTable::new(DEFAULT_HASH_MB)))
+    }
+    pub(crate) fn with_tt(params: SearchParams, tt: Arc<TranspositionTable>) -> Searcher {
+        let mut lmr_table = Box::new([[0; 64]; MAX_PLY]);
+        for depth in 1..MAX_PLY {
+            for move_number in 1..64 {
+                let reduction = params.lmr_base
+                    + (depth as f64).ln() * (move_number as f64).ln() / params.lmr_divisor;
+                lmr_table[depth][move_number] = reduction as usize;
+            }
+        }
+        Searcher {
+            nodes: 0,
+            ordering: MoveOrdering::new(),
+            use_ordering: true,
+            print_info: false,
+            chess960: false,
+            tt,
+            thread_id: 0,
+            completed_depth: 0,
+            node_limit: None,
+            time: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            pondering: Arc::new(AtomicBool::new(false)),
+            stopped: false,
+            can_stop: false,
+            evaluator: Evaluator::new(),
+            params,
+            lmr_table,
+            pv_table: Box::new([[None; MAX_PLY + 1]; MAX_PLY + 1]),
+            pv_length: [0; MAX_PLY + 1],
+            excluded: [None; MAX_PLY + 1],
+            extensions: [0; MAX_PLY + 1],
+        }
+    }
+    /// The principal variation of the last search.
+    pub fn pv(&self) -> Vec<Move> {
+        self.pv_table[0][..self.pv_length[0]]
+            .iter()
+            .flatten()
+            .copied()
+            .collect()
+    }
+    /// Fixed depth, full window search. `None` when stopped before finishing.
+    pub fn search(&mut self, state: &Position, depth: usize) -> Option<Evaluation> {
+        self.nodes = 0;
+        self.stopped = false;
+        self.can_stop = true;
+        self.extensions[0] = 0;
+        let score = self.negamax(state, depth, 0, -INFINITY, INFINITY, None);
+        if self.stopped {
+            return None;
+        }
+        let pv = self.pv();
+        let best_move = *pv.first()?;
+        Some(Evaluation {
+            best_move,
+            score,
+            pv,
+        })
+    }
+    /// Iterative deepening with aspiration windows around the previous iteration's
+    /// score, up to `max_depth` or the node and time limits.
+    pub fn iterate(&mut self, state: &Position, max_depth: usize) -> Option<Evaluation> {
+        self.nodes = 0;
+        self.stopped = false;
+        self.completed_depth = 0;
+        let mut result: Option<Evaluation> = None;
+        let mut instability = 0.0;
+        for depth in 1..=max_depth {
+            if self.thread_id > 0 {
+                let helper = (self.thread_id - 1) % SKIP_SIZE.len();
+                if (depth + SKIP_PHASE[helper]) / SKIP_SIZE[helper] % 2 == 1 {
+                    continue;
+                }
+            }
+            let mut delta = ASPIRATION_WINDOW;
+            let (mut alpha, mut beta) = match &result {
+                Some(eval) if depth >= ASPIRATION_DEPTH => (
+                    (eval.score - delta).max(-INFINITY),
+                    (eval.score + delta).min(INFINITY),
+                ),
+                _ => (-INFINITY, INFINITY),
+            };
+            self.can_stop = result.is_some();
+            let score = loop {
+                self.extensions[0] = 0;
+                let score = self.negamax(state, depth, 0, alpha, beta, None);
+                if self.stopped {
+                    break score;
+                }
+                if score <= alpha {
+                    beta = (alpha + beta) / 2;
+                    alpha = (score - delta).max(-INFINITY);
+                } else if score >= beta {
+                    beta = (score + delta).min(INFINITY);
+                } else {
+                    break score;
+                }
+                delta += delta;
+            };
+            // an interrupted iteration can't be trusted, keep the last complete one
+            if self.stopped {
+                break;
+            }
+            let pv = self.pv();
+            let Some(&best_move) = pv.first() else {
+                break;
+            };
+            if self.print_info {
+                println!(
+                    "{}",
+                    info_line(depth, score, self.nodes, &pv, self.chess960)
+                );
+            }
+            // a best move that keeps changing or a falling score earns more time
+            let mut score_drop = 0;
+            if let Some(previous) = &result {
+                instability *= 0.5;
+                if previous.best_move != best_move {
+                    instability += 1.0;
+                }
+                score_drop = previous.score - score;
+            }
+            result = Some(Evaluation {
+                best_move,
+                score,
+                pv,
+            });
+            self.completed_depth = depth;
+            if let Some(time) = self.clock() {
+                if time.soft_expired(instability, score_drop) {
+                    break;
+                }
+            }
+        }
+        result
+    }
+    /// [`iterate`](Self::iterate) on `threads` threads with Lazy SMP.
+    ///
+    /// Helpers search the same position with their own copies and ordering
+    /// tables, sharing only the transposition table. This searcher keeps the
+    /// clock and decides when everyone stops, then the deepest completed
+    /// iteration wins.
+    pub fn iterate_smp(
+        &mut self,
+        state: &Position,
+        max_depth: usize,
+        threads: usize,
+    ) -> Option<Evaluation> {
+        if threads <= 1 {
+            return self.iterate(state, max_depth);
+        }
+        let helpers_stop = Arc::new(AtomicBool::new(false));
+        let (mut result, helpers) = thread::scope(|scope| {
+            let handles: Vec<_> = (1..threads)
+                .map(|thread_id| {
+                    let mut helper = Searcher::with_tt(self.params, self.tt.clone());
+                    helper.thread_id = thread_id;
+                    helper.stop = helpers_stop.clone();
+                    helper.use_ordering = self.use_ordering;
+                    let state = state.clone();
+                    scope.spawn(move || {
+                        let result = helper.iterate(&state, MAX_PLY - 1);
+                        (result, helper.completed_depth, helper.nodes)
+                    })
+                })
+                .collect();
+            let result = self.iterate(state, max_depth);
+            helpers_stop.store(true, Ordering::Relaxed);
+            let helpers: Vec<_> = handles
+                .into_iter()
+                .map(|handle| handle.join().expect("search thread panicked"))
+                .collect();
+            (result, helpers)
+        });
+        for (helper_result, depth, nodes) in helpers {
+            self.nodes += nodes;
+            let Some(eval) = helper_result else {
+                continue;
+            };
+            let better = match &result {
+                None => true,
+                Some(best) => {
+                    depth > self.completed_depth
+                        || (depth == self.completed_depth && eval.score > best.score)
+                }
+            };
+            if better {
+                self.completed_depth = depth;
+                result = Some(eval);
+            }
+        }
+        result
+    }
+    // the clock and the stop flag are only read every CHECK_INTERVAL nodes
+    fn limit_reached(&self) -> bool {
+        if self.node_limit.is_some_and(|limit| self.nodes >= limit) {
+            return true;
+        }
+        self.nodes.is_multiple_of(CHECK_INTERVAL)
+            && (self.stop.load(Ordering::Relaxed)
+                || self.clock().is_some_and(|time| time.hard_expired()))
+    }
+    // the time limits, which count from `go` even after a ponderhit
+    fn clock(&self) -> Option<&TimeManager> {
+        match self.pondering.load(Ordering::Relaxed) {
+            true => None,
+            false => self.time.as_ref(),
+        }
+    }
+    fn update_pv(&mut self, ply: usize, piece_move: Move) {
+        self.pv_table[ply][ply] = Some(piece_move);
+        for next in ply + 1..self.pv_length[ply + 1] {
+            self.pv_table[ply][next] = self.pv_table[ply + 1][next];
+        }
+        self.pv_length[ply] = self.pv_length[ply + 1].max(ply + 1);
+    }
+    // `previous` is None at the root and right after a null move
+    fn negamax(
+        &mut self,
+        state: &Position,
+        mut depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        previous: Option<Move>,
+    ) -> i32 {
+        if self.can_stop && self.limit_reached() {
+            self.stopped = true;
+        }
+        if self.stopped {
+            return 0;
+        }
+        self.nodes += 1;
+        self.pv_length[ply] = ply;
+        let excluded = self.excluded[ply];
+        let mut moves = state.moves(state.active_color);
+        let checked = state.in_check();
+        if moves.is_empty() {
+            return if checked { -MATE + ply as i32 } else { 0 };
+        }
+        let params = self.params;
+        if params.check_extensions && checked && self.extensions[ply] < params.max_extensions {
+            depth += 1;
+            self.extensions[ply] += 1;
+        }
+        if depth == 0 || ply >= MAX_PLY {
+            return self.evaluator.evaluate(state);
+        }
+        let pv_node = beta - alpha > 1;
+        let original_alpha = alpha;
+
+        // verification searches skip the TT, their result excludes a move
+        let tt_entry = match excluded {
+            None => self.tt.probe(state.hash),
+            Some(_) => None,
+        };
+        if let Some(entry) = tt_entry {
+            let score = score_from_tt(entry.score, ply);
+            if !pv_node && entry.depth >= depth {
+                match entry.bound {
+                    Bound::Exact => return score,
+                    Bound::Lower if score >= beta => return score,
+                    Bound::Upper if score <= alpha => return score,
+                    _ => {}
+                }
+            }
+        }
+        let tt_move = tt_entry.and_then(|entry| entry.best_move);
+        let static_eval = self.evaluator.evaluate(state);
+
+        if !pv_node && !checked && excluded.is_none() {
+            if params.reverse_futility
+                && depth <= params.reverse_futility_depth
+                && static_eval - params.reverse_futility_margin * depth as i32 >= beta
+            {
+                return static_eval;
+            }
+            // no null move twice in a row, and never without pieces (zugzwang)
+            if params.null_move
+                && depth >= params.null_move_min_depth
+                && previous.is_some()
+                && static_eval >= beta
+                && !state
+                    .pieces
+                    .non_pawn_material(state.active_color)
+                    .is_empty()
+            {
+                let reduction = params.null_move_reduction + depth / 6;
+                let null_gamestate = state.apply_null_move();
+                self.extensions[ply + 1] = self.extensions[ply];
+                let score = -self.negamax(
+                    &null_gamestate,
+                    depth.saturating_sub(1 + reduction),
+                    ply + 1,
+                    -beta,
+                    -beta + 1,
+                    None,
+                );
+                if score >= beta {
+                    return if is_mate_score(score) { beta } else { score };
+                }
+            }
+        }
+
+        if self.use_ordering {
+            moves = self.ordering.order(state, moves, ply, previous, tt_move);
+        }
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        let mut quiets_tried: Vec<Move> = vec![];
+        let mut quiets_seen = 0;
+        let mut moves_searched = 0;
+        for (i, piece_move) in moves.into_iter().enumerate() {
+            if excluded == Some(piece_move) {
+                continue;
+            }
+            let quiet = is_quiet(state, piece_move);
+            if quiet {
+                quiets_seen += 1;
+            }
+            // singular extension: extend the TT move if every alternative fails low
+            // against a margin below its score in a reduced depth search without it
+            let mut extension = 0;
+            if let (Some(entry), true) = (tt_entry, tt_move == Some(piece_move)) {
+                if params.singular_extensions
+                    && ply > 0
+                    && depth >= params.singular_min_depth
+                    && entry.depth + 3 >= depth
+                    && entry.bound != Bound::Upper
+                    && !is_mate_score(entry.score)
+                    && self.extensions[ply] < params.max_extensions
+                {
+                    let singular_beta = score_from_tt(entry.score, ply) - 2 * depth as i32;
+                    let extensions = self.extensions[ply];
+                    self.excluded[ply] = Some(piece_move);
+                    let score = self.negamax(
+                        state,
+                        (depth - 1) / 2,
+                        ply,
+                        singular_beta - 1,
+                        singular_beta,
+                        previous,
+                    );
+                    self.excluded[ply] = None;
+                    self.extensions[ply] = extensions;
+                    // it ran at this ply, so drop the line it left in the PV table
+                    self.pv_length[ply] = ply;
+                    if score < singular_beta {
+                        extension = 1;
+                    }
+                }
+            }
+            let gives_check = state.gives_check(piece_move);
+            // prune quiet moves only once a real score has been found
+            if quiet && !pv_node && !checked && !gives_check && !is_mate_score(best_score) {
+                if params.late_move_pruning
+                    && depth <= params.late_move_pruning_depth
+                    && quiets_seen > params.late_move_pruning_base + depth * depth
+                {
+                    continue;
+                }
+                if params.futility
+                    && depth <= params.futility_depth
+                    && static_eval + params.futility_margin * depth as i32 <= alpha
+                {
+                    continue;
+                }
+            }
+            let new_gamestate = state.apply_move(piece_move);
+            let new_depth = depth - 1 + extension;
+            let mut reduction = 0;
+            if params.lmr
+                && quiet
+                && depth >= params.lmr_min_depth
+                && i >= params.lmr_min_moves
+                && !checked
+                && !gives_check
+            {
+                reduction = self.lmr_table[depth.min(MAX_PLY - 1)][i.min(63)];
+                if pv_node {
+                    reduction = reduction.saturating_sub(1);
+                }
+                reduction = reduction.min(new_depth.saturating_sub(1));
+            }
+            self.extensions[ply + 1] = self.extensions[ply] + extension;
+            let child = Some(piece_move);
+            // PVS: only the first move gets a full window, the rest have to prove they beat alpha
+            let score = if moves_searched == 0 {
+                -self.negamax(&new_gamestate, new_depth, ply + 1, -beta, -alpha, child)
+            } else {
+                let mut score = -self.negamax(
+                    &new_gamestate,
+                    new_depth - reduction,
+                    ply + 1,
+                    -alpha - 1,
+                    -alpha,
+                    child,
+                );
+                if reduction > 0 && score > alpha {
+                    score = -self.negamax(
+                        &new_gamestate,
+                        new_depth,
+                        ply + 1,
+                        -alpha - 1,
+                        -alpha,
+                        child,
+                    );
+                }
+                if score > alpha && score < beta {
+                    score = -self.negamax(&new_gamestate, new_depth, ply + 1, -beta, -alpha, child);
+                }
+                score
+            };
+            if self.stopped {
+                return 0;
+            }
+            moves_searched += 1;
+            if score > best_score {
+                best_score = score;
+                best_move = Some(piece_move);
+            }
+            if score > alpha {
+                alpha = score;
+                self.update_pv(ply, piece_move);
+            }
+            if alpha >= beta {
+                if quiet && self.use_ordering {
+                    self.ordering
+                        .update_quiet(piece_move, ply, depth, previous, &quiets_tried);
+                }
+                break;
+            }
+            if quiet {
+                quiets_tried.push(piece_move);
+            }
+        }
+        if excluded.is_none() {
+            let bound = if best_score >= beta {
+                Bound::Lower
+            } else if best_score > original_alpha {
+                Bound::Exact
+            } else {
+                Bound::Upper
+            };
+            let score = score_to_tt(best_score, ply);
+            self.tt.store(state.hash, best_move, score, depth, bound);
+        }
+        best_score
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub(crate) struct BenchNodes {
+    pub selective: u64,
+    pub full_width: u64,
+    pub unordered: u64,
+}
+
+impl BenchNodes {
+    pub(crate) fn total(results: &[BenchResult]) -> BenchNodes {
+        results
+            .iter()
+            .fold(BenchNodes::default(), |total, result| BenchNodes {
+                selective: total.selective + result.nodes.selective,
+                full_width: total.full_width + result.nodes.full_width,
+                unordered: total.unordered + result.nodes.unordered,
+            })
+    }
+}
+
+pub(crate) struct BenchResult {
+    pub fen: &'static str,
+    // of the search with the default parameters
+    pub evaluation: Option<Evaluation>,
+    pub nodes: BenchNodes,
+}
+
+// searches every bench position to `depth` with the default parameters, without
+// selective search, and without selective search or move ordering
+pub(crate) fn bench(depth: usize) -> Vec<BenchResult> {
+    BENCH_POSITIONS
+        .into_iter()
+        .map(|fen| {
+            let state = Position::new(fen.to_owned());
+            let mut nodes = [0u64; 3];
+            let mut evaluation = None;
+            let configs = [
+                (SearchParams::default(), true),
+                (SearchParams::full_width(), true),
+                (SearchParams::full_width(), false),
+            ];
+            for (i, (params, use_ordering)) in configs.into_iter().enumerate() {
+                let mut searcher = Searcher::with_params(params);
+                searcher.use_ordering = use_ordering;
+                let result = searcher.search(&state, depth);
+                nodes[i] = searcher.nodes;
+                if i == 0 {
+                    evaluation = result;
+                }
+            }
+            BenchResult {
+                fen,
+                evaluation,
+                nodes: BenchNodes {
+                    selective: nodes[0],
+                    full_width: nodes[1],
+                    unordered: nodes[2],
+                },
+            }
+        })
+        .collect()
+}
@@ -1,60 +1,48 @@
-pub fn sout_attacks(mut pieces: u64, empty: u64) -> u64 {
+use crate::util::Bitboard;
+use crate::Direction;
+
+// squares reached from `pieces` sliding in one direction, stopping on the first
+// square that isn't in `empty`
+fn attacks(mut pieces: Bitboard, empty: Bitboard, direction: Direction) -> Bitboard {
     for _ in 0..7 {
-        pieces |= (pieces >> 8) & empty;
+        pieces |= pieces.shift(direction) & empty;
     }
-    pieces >> 8
+    pieces.shift(direction)
 }
-pub fn nort_attacks(mut pieces: u64, empty: u64) -> u64 {
-    for _ in 0..7 {
-        pieces |= (pieces << 8) & empty;
-    }
-    pieces << 8
+pub fn sout_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    attacks(pieces, empty, Direction::South)
 }
-pub fn east_attacks(mut pieces: u64, mut empty: u64) -> u64 {
-    const NOT_A: u64 = 0xfefefefefefefefe;
-    empty &= NOT_A;
-    for _ in 0..7 {
-        pieces |= (pieces << 1) & empty;
-    }
-    (pieces << 1) & NOT_A
+pub fn nort_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    attacks(pieces, empty, Direction::North)
 }
-pub fn west_attacks(mut pieces: u64, mut empty: u64) -> u64 {
-    const NOT_H: u64 = 0x7f7f7f7f7f7f7f7f;
-    empty &= NOT_H;
-    for _ in 0..7 {
-        pieces |= (pieces >> 1) & empty;
-    }
-    (pieces >> 1) & NOT_H
+pub fn east_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    attacks(pieces, empty, Direction::East)
 }
-pub fn noea_attacks(mut pieces: u64, mut empty: u64) -> u64 {
-    const NOT_A: u64 = 0xfefefefefefefefe;
-    empty &= NOT_A;
-    for _ in 0..7 {
-        pieces |= (pieces << 9) & empty;
-    }
-    (pieces << 9) & NOT_A
+pub fn west_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    attacks(pieces, empty, Direction::West)
 }
-pub fn nowe_attacks(mut pieces: u64, mut empty: u64) -> u64 {
-    const NOT_H: u64 = 0x7f7f7f7f7f7f7f7f;
-    empty &= NOT_H;
-    for _ in 0..7 {
-        pieces |= (pieces << 7) & empty;
-    }
-    (pieces << 7) & NOT_H
+pub fn noea_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    attacks(pieces, empty, Direction::NorthEast)
 }
-pub fn soea_attacks(mut pieces: u64, mut empty: u64) -> u64 {
-    const NOT_A: u64 = 0xfefefefefefefefe;
-    empty &= NOT_A;
-    for _ in 0..7 {
-        pieces |= (pieces >> 7) & empty;
-    }
-    (pieces >> 7) & NOT_A
+pub fn nowe_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    attacks(pieces, empty, Direction::NorthWest)
 }
-pub fn sowe_attacks(mut pieces: u64, mut empty: u64) -> u64 {
-    const NOT_H: u64 = 0x7f7f7f7f7f7f7f7f;
-    empty &= NOT_H;
-    for _ in 0..7 {
-        pieces |= (pieces >> 9) & empty;
-    }
-    (pieces >> 9) & NOT_H
+pub fn soea_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    attacks(pieces, empty, Direction::SouthEast)
+}
+pub fn sowe_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    attacks(pieces, empty, Direction::SouthWest)
+}
+// the four straight and the four diagonal rays together
+pub fn rook_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    nort_attacks(pieces, empty)
+        | east_attacks(pieces, empty)
+        | sout_attacks(pieces, empty)
+        | west_attacks(pieces, empty)
+}
+pub fn bishop_attacks(pieces: Bitboard, empty: Bitboard) -> Bitboard {
+    noea_attacks(pieces, empty)
+        | soea_attacks(pieces, empty)
+        | sowe_attacks(pieces, empty)
+        | nowe_attacks(pieces, empty)
 }
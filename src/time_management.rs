@@ -0,0 +1,122 @@
+//! Time limits for a search, worked out from a UCI clock.
+//!
+//! ```
+//! use bitboard_generator::search::Searcher;
+//! use bitboard_generator::time_management::{Clock, TimeManager};
+//! use bitboard_generator::{Color, Position};
+//!
+//! let mut clock = Clock::default();
+//! clock.movetime = Some(50);
+//! let mut searcher = Searcher::new();
+//! searcher.time = TimeManager::new(&clock, Color::White, 0);
+//! assert!(searcher.iterate(&Position::default(), 60).is_some());
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::Color;
+
+pub(crate) const DEFAULT_MOVE_OVERHEAD: u64 = 30; // ms kept back for communication lag
+pub(crate) const CHECK_INTERVAL: u64 = 1024; // nodes between clock reads
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+const MAX_MOVES_TO_GO: u64 = 50;
+// how far past the soft limit an unstable search may run, as a multiple of it
+const MAX_SOFT_SCALE: f64 = 2.5;
+
+/// The clock fields of a UCI `go` command, in milliseconds.
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct Clock {
+    /// White's time left.
+    pub wtime: Option<u64>,
+    /// Black's time left.
+    pub btime: Option<u64>,
+    /// White's increment per move.
+    pub winc: u64,
+    /// Black's increment per move.
+    pub binc: u64,
+    /// Moves until the next time control, sudden death when `None`.
+    pub movestogo: Option<u64>,
+    /// Search exactly this long, whatever the clocks say.
+    pub movetime: Option<u64>,
+}
+
+/// The limits of one search: past the soft limit no new iteration starts, past
+/// the hard limit the search is abandoned mid-iteration.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeManager {
+    start: Instant,
+    soft: Duration,
+    hard: Duration,
+}
+
+impl TimeManager {
+    /// The limits for `side` to move, counted from now, keeping `overhead`
+    /// milliseconds back for communication lag. `None` when the clock sets no
+    /// limit for `side`.
+    pub fn new(clock: &Clock, side: Color, overhead: u64) -> Option<TimeManager> {
+        TimeManager::starting_at(Instant::now(), clock, side, overhead)
+    }
+    /// [`new`](Self::new) counted from `start`, an earlier one spends part of
+    /// the clock.
+    pub fn starting_at(
+        start: Instant,
+        clock: &Clock,
+        side: Color,
+        overhead: u64,
+    ) -> Option<TimeManager> {
+        if let Some(movetime) = clock.movetime {
+            let limit = Duration::from_millis(movetime.saturating_sub(overhead).max(1));
+            return Some(TimeManager {
+                start,
+                soft: limit,
+                hard: limit,
+            });
+        }
+        let (time, increment) = match side {
+            Color::White => (clock.wtime?, clock.winc),
+            Color::Black => (clock.btime?, clock.binc),
+        };
+        let available = time.saturating_sub(overhead).max(1);
+        let moves_to_go = clock
+            .movestogo
+            .unwrap_or(DEFAULT_MOVES_TO_GO)
+            .clamp(1, MAX_MOVES_TO_GO);
+        let soft = available / moves_to_go + increment * 3 / 4;
+        // an unstable search may overrun its share, but never by more than four
+        // times and never into the last quarter of the clock
+        let hard = (soft * 4).min(available * 3 / 4).max(1);
+        let soft = soft.min(hard);
+        Some(TimeManager {
+            start,
+            soft: Duration::from_millis(soft),
+            hard: Duration::from_millis(hard),
+        })
+    }
+    /// The time since the limits started counting.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+    /// The time after which the search is abandoned.
+    pub fn hard_limit(&self) -> Duration {
+        self.hard
+    }
+    /// Whether the hard limit has passed.
+    pub fn hard_expired(&self) -> bool {
+        self.elapsed() >= self.hard_limit()
+    }
+    /// The time after which no new iteration starts. `instability` grows with
+    /// recent best move changes and `score_drop` is how many centipawns the score
+    /// fell since the last iteration; both stretch the limit, up to the hard one.
+    pub fn soft_limit(&self, instability: f64, score_drop: i32) -> Duration {
+        let mut scale = 1.0 + instability * 0.4;
+        if score_drop > 0 {
+            scale *= 1.0 + score_drop.min(100) as f64 / 200.0;
+        }
+        self.soft.mul_f64(scale.min(MAX_SOFT_SCALE)).min(self.hard)
+    }
+    /// Whether the soft limit has passed, checked between iterations.
+    pub fn soft_expired(&self, instability: f64, score_drop: i32) -> bool {
+        self.elapsed() >= self.soft_limit(instability, score_drop)
+    }
+}
@@ -0,0 +1,158 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::search::{is_mate_score, MATE};
+use crate::util::Square;
+use crate::{Color, Move, PromotionType};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    Exact,
+    Lower, // fail high, score is at least this
+    Upper, // fail low, score is at most this
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TTEntry {
+    pub best_move: Option<Move>,
+    pub score: i32,
+    pub depth: usize,
+    pub bound: Bound,
+}
+
+// mate scores are stored relative to the node so they stay valid at other plies
+pub fn score_to_tt(score: i32, ply: usize) -> i32 {
+    if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        score + ply as i32
+    } else {
+        score - ply as i32
+    }
+}
+
+pub fn score_from_tt(score: i32, ply: usize) -> i32 {
+    if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        (score - ply as i32).min(MATE)
+    } else {
+        (score + ply as i32).max(-MATE)
+    }
+}
+
+// Move packed into 16 bits: from, to, colour, promotion (0 for none, 5 for castling);
+// all ones for None
+fn pack_move(best_move: Option<Move>) -> u64 {
+    match best_move {
+        None => 0xffff,
+        Some(m) => {
+            let promotion = match m.promoted_piece {
+                None if m.castling => 5,
+                None => 0,
+                Some(PromotionType::Queen) => 1,
+                Some(PromotionType::Rook) => 2,
+                Some(PromotionType::Bishop) => 3,
+                Some(PromotionType::Knight) => 4,
+            };
+            (m.from.index() | m.to.index() << 6 | (m.piece_color as usize) << 12 | promotion << 13)
+                as u64
+        }
+    }
+}
+
+fn unpack_move(bits: u64) -> Option<Move> {
+    if bits == 0xffff {
+        return None;
+    }
+    let bits = bits as usize;
+    Some(Move {
+        from: Square((bits & 63) as u8),
+        to: Square((bits >> 6 & 63) as u8),
+        piece_color: if bits >> 12 & 1 == 0 {
+            Color::White
+        } else {
+            Color::Black
+        },
+        promoted_piece: match bits >> 13 {
+            1 => Some(PromotionType::Queen),
+            2 => Some(PromotionType::Rook),
+            3 => Some(PromotionType::Bishop),
+            4 => Some(PromotionType::Knight),
+            _ => None,
+        },
+        castling: bits >> 13 == 5,
+    })
+}
+
+// move | score | depth | bound, bound 0 marks an empty slot
+fn pack(best_move: Option<Move>, score: i32, depth: usize, bound: Bound) -> u64 {
+    let bound = match bound {
+        Bound::Exact => 1,
+        Bound::Lower => 2,
+        Bound::Upper => 3,
+    };
+    pack_move(best_move)
+        | (score as i16 as u16 as u64) << 16
+        | (depth.min(255) as u64) << 32
+        | bound << 40
+}
+
+fn unpack(data: u64) -> Option<TTEntry> {
+    let bound = match data >> 40 & 0xff {
+        1 => Bound::Exact,
+        2 => Bound::Lower,
+        3 => Bound::Upper,
+        _ => return None,
+    };
+    Some(TTEntry {
+        best_move: unpack_move(data & 0xffff),
+        score: (data >> 16) as u16 as i16 as i32,
+        depth: (data >> 32 & 0xff) as usize,
+        bound,
+    })
+}
+
+// the key is stored xored with the data, so an entry torn by two threads writing
+// at once fails the key check instead of returning a mix of both
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+// shared between search threads without locks
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+}
+
+impl TranspositionTable {
+    pub fn new(megabytes: usize) -> TranspositionTable {
+        let len = (megabytes * 1024 * 1024 / std::mem::size_of::<Slot>()).max(1);
+        TranspositionTable {
+            slots: (0..len).map(|_| Slot::default()).collect(),
+        }
+    }
+    fn slot(&self, key: u64) -> &Slot {
+        &self.slots[(key % self.slots.len() as u64) as usize]
+    }
+    pub fn probe(&self, key: u64) -> Option<TTEntry> {
+        let slot = self.slot(key);
+        let data = slot.data.load(Ordering::Relaxed);
+        if slot.key.load(Ordering::Relaxed) ^ data != key {
+            return None;
+        }
+        unpack(data)
+    }
+    // always replace, whatever the depth; a store without a move (every move failed
+    // low) keeps the move already there for the same position
+    pub fn store(&self, key: u64, best_move: Option<Move>, score: i32, depth: usize, bound: Bound) {
+        let best_move = match self.probe(key) {
+            Some(old) if best_move.is_none() => old.best_move,
+            _ => best_move,
+        };
+        let data = pack(best_move, score, depth, bound);
+        let slot = self.slot(key);
+        slot.key.store(key ^ data, Ordering::Relaxed);
+        slot.data.store(data, Ordering::Relaxed);
+    }
+}
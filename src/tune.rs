@@ -0,0 +1,225 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+use crate::evaluation::{self, Score, Weights, MAX_PHASE, PARAM_COUNT, SECTIONS};
+use crate::weights::WEIGHTS;
+use crate::{Color, Position};
+
+// Texel tuning: the evaluation is linear in its weights apart from a few fixed
+// terms, so every position is reduced once to how often each weight is used and
+// Adam then minimises the squared error between sigmoid(eval) and the game result
+
+const LEARNING_RATE: f64 = 1.0;
+const BETA1: f64 = 0.9;
+const BETA2: f64 = 0.999;
+const EPSILON: f64 = 1e-8;
+
+// white minus black uses of each weight
+#[derive(Default)]
+struct Coefficients {
+    counts: Vec<(usize, i32)>,
+}
+
+impl Weights for Coefficients {
+    fn weight(&mut self, color: Color, param: usize, count: i32) -> Score {
+        let sign = match color {
+            Color::White => 1,
+            Color::Black => -1,
+        };
+        self.counts.push((param, sign * count));
+        Score::default()
+    }
+}
+
+pub struct Entry {
+    coefficients: Vec<(usize, i32)>,
+    fixed: Score, // terms that aren't tuned, such as the king attack
+    phase: i32,
+    result: f64, // from White's point of view, 1 for a win
+}
+
+impl Entry {
+    pub fn new(state: &Position, result: f64) -> Entry {
+        let mut recorder = Coefficients::default();
+        let (fixed, phase) = evaluation::evaluate_weighted(state, &mut recorder);
+        let mut counts = recorder.counts;
+        counts.sort_by_key(|&(param, _)| param);
+        let mut coefficients: Vec<(usize, i32)> = vec![];
+        for (param, count) in counts {
+            match coefficients.last_mut() {
+                Some(last) if last.0 == param => last.1 += count,
+                _ => coefficients.push((param, count)),
+            }
+        }
+        coefficients.retain(|&(_, count)| count != 0);
+        Entry {
+            coefficients,
+            fixed,
+            phase,
+            result,
+        }
+    }
+    // white's point of view, the tapered sum without integer rounding
+    pub fn evaluate(&self, params: &[[f64; 2]]) -> f64 {
+        let (mut mg, mut eg) = (self.fixed.0 as f64, self.fixed.1 as f64);
+        for &(param, count) in &self.coefficients {
+            mg += params[param][0] * count as f64;
+            eg += params[param][1] * count as f64;
+        }
+        let phase = self.phase as f64;
+        (mg * phase + eg * (MAX_PHASE as f64 - phase)) / MAX_PHASE as f64
+    }
+}
+
+pub fn initial_params() -> Vec<[f64; 2]> {
+    WEIGHTS
+        .iter()
+        .map(|score| [score.0 as f64, score.1 as f64])
+        .collect()
+}
+
+fn sigmoid(k: f64, eval: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * eval / 400.0))
+}
+
+pub fn mean_squared_error(entries: &[Entry], params: &[[f64; 2]], k: f64) -> f64 {
+    let total: f64 = entries
+        .iter()
+        .map(|entry| (entry.result - sigmoid(k, entry.evaluate(params))).powi(2))
+        .sum();
+    total / entries.len().max(1) as f64
+}
+
+// golden-section search for the scaling that best maps the current evaluation to results
+pub fn fit_k(entries: &[Entry], params: &[[f64; 2]]) -> f64 {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = (0.0, 10.0);
+    while high - low > 1e-4 {
+        let a = high - ratio * (high - low);
+        let b = low + ratio * (high - low);
+        if mean_squared_error(entries, params, a) < mean_squared_error(entries, params, b) {
+            high = b;
+        } else {
+            low = a;
+        }
+    }
+    (low + high) / 2.0
+}
+
+fn gradient(entries: &[Entry], params: &[[f64; 2]], k: f64) -> Vec<[f64; 2]> {
+    let mut gradient = vec![[0.0; 2]; params.len()];
+    let scale = 2.0 * k * 10f64.ln() / 400.0 / entries.len().max(1) as f64;
+    for entry in entries {
+        let s = sigmoid(k, entry.evaluate(params));
+        let error = (s - entry.result) * s * (1.0 - s) * scale;
+        let mg = error * entry.phase as f64 / MAX_PHASE as f64;
+        let eg = error * (MAX_PHASE - entry.phase) as f64 / MAX_PHASE as f64;
+        for &(param, count) in &entry.coefficients {
+            gradient[param][0] += mg * count as f64;
+            gradient[param][1] += eg * count as f64;
+        }
+    }
+    gradient
+}
+
+// full-batch Adam; `report` sees the error after every epoch
+pub fn tune(
+    entries: &[Entry],
+    params: &mut [[f64; 2]],
+    k: f64,
+    epochs: usize,
+    mut report: impl FnMut(usize, f64),
+) {
+    let mut momentum = vec![[0.0; 2]; params.len()];
+    let mut velocity = vec![[0.0; 2]; params.len()];
+    for epoch in 1..=epochs {
+        let gradient = gradient(entries, params, k);
+        let correction1 = 1.0 - BETA1.powi(epoch as i32);
+        let correction2 = 1.0 - BETA2.powi(epoch as i32);
+        for param in 0..params.len() {
+            for phase in 0..2 {
+                let g = gradient[param][phase];
+                momentum[param][phase] = BETA1 * momentum[param][phase] + (1.0 - BETA1) * g;
+                velocity[param][phase] = BETA2 * velocity[param][phase] + (1.0 - BETA2) * g * g;
+                let m = momentum[param][phase] / correction1;
+                let v = velocity[param][phase] / correction2;
+                params[param][phase] -= LEARNING_RATE * m / (v.sqrt() + EPSILON);
+            }
+        }
+        report(epoch, mean_squared_error(entries, params, k));
+    }
+}
+
+fn parse_result(label: &str) -> Option<f64> {
+    match label.trim().trim_matches('"') {
+        "1-0" => Some(1.0),
+        "0-1" => Some(0.0),
+        "1/2-1/2" => Some(0.5),
+        other => other.parse().ok(),
+    }
+}
+
+// "<fen> [1.0]", "<fen> [1/2-1/2]" or "<fen> c9 \"0-1\";", the result from White's side
+pub fn parse_line(line: &str) -> Option<(String, f64)> {
+    let line = line.trim().trim_end_matches(';');
+    let (fen, result) = match line.rfind('[') {
+        Some(index) => (
+            &line[..index],
+            parse_result(line[index + 1..].trim_end_matches(']'))?,
+        ),
+        None => {
+            let (fen, label) = line.rsplit_once(' ')?;
+            (fen, parse_result(label)?)
+        }
+    };
+    let mut fields: Vec<&str> = fen.split_whitespace().collect();
+    if fields.len() < 4 {
+        return None;
+    }
+    // EPD lines leave out the move clocks
+    fields.truncate(4);
+    Some((format!("{} 0 1", fields.join(" ")), result))
+}
+
+pub fn load(path: &str) -> io::Result<Vec<Entry>> {
+    read(BufReader::new(File::open(path)?))
+}
+
+// lines without a result are skipped, a labelled line with a bad FEN is an error
+pub fn read(reader: impl BufRead) -> io::Result<Vec<Entry>> {
+    let mut entries = vec![];
+    for (number, line) in reader.lines().enumerate() {
+        if let Some((fen, result)) = parse_line(&line?) {
+            let position = fen.parse::<Position>().map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", number + 1, error),
+                )
+            })?;
+            entries.push(Entry::new(&position, result));
+        }
+    }
+    Ok(entries)
+}
+
+// the contents of src/weights.rs for `params`
+pub fn write_source(params: &[[f64; 2]]) -> String {
+    let mut source = String::from(
+        "// generated by `tune`, evaluation::SECTIONS describes the layout\n\
+         use crate::evaluation::{Score, PARAM_COUNT};\n\n\
+         #[rustfmt::skip]\n\
+         pub static WEIGHTS: [Score; PARAM_COUNT] = [\n",
+    );
+    for (i, (name, start)) in SECTIONS.into_iter().enumerate() {
+        let end = SECTIONS.get(i + 1).map_or(PARAM_COUNT, |section| section.1);
+        source += &format!("    // {}\n", name);
+        for row in params[start..end].chunks(8) {
+            let scores: Vec<String> = row
+                .iter()
+                .map(|[mg, eg]| format!("Score({}, {}),", mg.round() as i32, eg.round() as i32))
+                .collect();
+            source += &format!("    {}\n", scores.join(" "));
+        }
+    }
+    source + "];\n"
+}
@@ -0,0 +1,151 @@
+//! The small value types shared by the whole crate.
+
+use crate::util::Square;
+
+/// A side, White moves first.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum Color {
+    /// The side that moves first.
+    White,
+    /// The side that moves second.
+    Black,
+}
+pub(crate) trait Invert {
+    fn invert(&self) -> Color;
+}
+impl Invert for Color {
+    fn invert(&self) -> Color {
+        match self {
+            Self::White => Self::Black,
+            Self::Black => Self::White,
+        }
+    }
+}
+/// The kind of a piece, `as usize` indexes per-piece tables in this order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum PieceType {
+    /// The king, index 0.
+    King,
+    /// The queen, index 1.
+    Queen,
+    /// A rook, index 2.
+    Rook,
+    /// A bishop, index 3.
+    Bishop,
+    /// A knight, index 4.
+    Knight,
+    /// A pawn, index 5.
+    Pawn,
+}
+/// The piece a pawn becomes on the last rank.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum PromotionType {
+    /// Promote to a queen, `q` in UCI.
+    Queen,
+    /// Promote to a rook, `r` in UCI.
+    Rook,
+    /// Promote to a bishop, `b` in UCI.
+    Bishop,
+    /// Promote to a knight, `n` in UCI.
+    Knight,
+}
+impl PromotionType {
+    pub(crate) fn piece_type(self) -> PieceType {
+        match self {
+            Self::Queen => PieceType::Queen,
+            Self::Rook => PieceType::Rook,
+            Self::Bishop => PieceType::Bishop,
+            Self::Knight => PieceType::Knight,
+        }
+    }
+}
+/// The eight ways to step from a square to a neighbouring one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Direction {
+    /// Towards the eighth rank.
+    North,
+    /// Towards the eighth rank and the h-file.
+    NorthEast,
+    /// Towards the h-file.
+    East,
+    /// Towards the first rank and the h-file.
+    SouthEast,
+    /// Towards the first rank.
+    South,
+    /// Towards the first rank and the a-file.
+    SouthWest,
+    /// Towards the a-file.
+    West,
+    /// Towards the eighth rank and the a-file.
+    NorthWest,
+}
+/// A move as the move generator produces it. Castling is the king taking its own
+/// rook, so it is the same in Chess960, en passant the pawn moving onto the en
+/// passant square.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Move {
+    pub(crate) from: Square,
+    pub(crate) to: Square,
+    pub(crate) promoted_piece: Option<PromotionType>,
+    pub(crate) piece_color: Color,
+    pub(crate) castling: bool,
+}
+impl Move {
+    /// The square the piece leaves.
+    pub fn from(&self) -> Square {
+        self.from
+    }
+    /// The square the piece lands on, the castling rook's square when castling.
+    pub fn to(&self) -> Square {
+        self.to
+    }
+    /// The piece a pawn promotes to, if it does.
+    pub fn promotion(&self) -> Option<PromotionType> {
+        self.promoted_piece
+    }
+    /// The side making the move.
+    pub fn color(&self) -> Color {
+        self.piece_color
+    }
+    /// Whether the move castles, see [`to`](Self::to).
+    pub fn is_castling(&self) -> bool {
+        self.castling
+    }
+    // where the piece ends up, the king's square when castling
+    pub(crate) fn king_to(&self) -> Square {
+        match (self.castling, self.to > self.from) {
+            (false, _) => self.to,
+            (true, true) => Square::new(6, self.from.rank()),
+            (true, false) => Square::new(2, self.from.rank()),
+        }
+    }
+    /// The move in UCI notation, like `e2e4`, `e7e8q` or `e1g1` for castling.
+    pub fn display(&self) -> String {
+        self.uci(false)
+    }
+    /// The move in UCI notation, castling as the king taking its rook when
+    /// `chess960` is set, as `UCI_Chess960` asks for.
+    pub fn uci(&self, chess960: bool) -> String {
+        let promotion = match self.promoted_piece {
+            None => "",
+            Some(PromotionType::Queen) => "q",
+            Some(PromotionType::Rook) => "r",
+            Some(PromotionType::Bishop) => "b",
+            Some(PromotionType::Knight) => "n",
+        };
+        let to = if chess960 { self.to } else { self.king_to() };
+        format!("{}{}{}", self.from, to, promotion)
+    }
+}
+/// A piece of one color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Piece {
+    /// The side the piece belongs to.
+    pub color: Color,
+    /// What kind of piece it is.
+    pub piece_type: PieceType,
+}
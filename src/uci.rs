@@ -0,0 +1,254 @@
+//! The UCI protocol, for playing through a chess GUI.
+
+use std::io::{self, BufRead};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::search::Evaluation;
+use crate::search::{Searcher, DEFAULT_HASH_MB, MAX_PLY};
+use crate::time_management::{Clock, TimeManager, DEFAULT_MOVE_OVERHEAD};
+use crate::transposition::TranspositionTable;
+use crate::{fen, perft};
+use crate::{Move, Position};
+
+const MAX_THREADS: usize = 256;
+
+/// The legal move that prints as `text` in UCI notation, like `e7e8q`, with
+/// castling as the king taking its rook when `chess960` is set.
+pub fn parse_move(state: &Position, text: &str, chess960: bool) -> Option<Move> {
+    state
+        .moves(state.active_color)
+        .into_iter()
+        .find(|piece_move| piece_move.uci(chess960) == text)
+}
+
+// "position [startpos | fen <fen>] [moves <move>...]"
+pub(crate) fn parse_position(tokens: &[&str], chess960: bool) -> Option<Position> {
+    let moves_at = tokens
+        .iter()
+        .position(|&token| token == "moves")
+        .unwrap_or(tokens.len());
+    let mut state = match tokens.first() {
+        Some(&"startpos") => Position::new(fen::START_FEN.to_owned()),
+        Some(&"fen") => tokens[1..moves_at].join(" ").parse::<Position>().ok()?,
+        _ => return None,
+    };
+    for text in tokens.iter().skip(moves_at + 1) {
+        state = state.apply_move(parse_move(&state, text, chess960)?);
+    }
+    Some(state)
+}
+
+struct Go {
+    clock: Clock,
+    depth: Option<usize>,
+    nodes: Option<u64>,
+    infinite: bool,
+    ponder: bool,
+}
+
+fn parse_go(tokens: &[&str]) -> Go {
+    let mut go = Go {
+        clock: Clock::default(),
+        depth: None,
+        nodes: None,
+        infinite: tokens.contains(&"infinite"),
+        ponder: tokens.contains(&"ponder"),
+    };
+    for pair in tokens.windows(2) {
+        let value = || pair[1].parse::<u64>().ok();
+        match pair[0] {
+            "wtime" => go.clock.wtime = value(),
+            "btime" => go.clock.btime = value(),
+            "winc" => go.clock.winc = value().unwrap_or(0),
+            "binc" => go.clock.binc = value().unwrap_or(0),
+            "movestogo" => go.clock.movestogo = value(),
+            "movetime" => go.clock.movetime = value(),
+            "depth" => go.depth = value().map(|depth| depth as usize),
+            "nodes" => go.nodes = value(),
+            _ => {}
+        }
+    }
+    go
+}
+
+pub(crate) fn print_bestmove(result: Option<Evaluation>, chess960: bool) {
+    match result {
+        Some(eval) => match eval.pv.get(1) {
+            Some(ponder) => println!(
+                "bestmove {} ponder {}",
+                eval.best_move.uci(chess960),
+                ponder.uci(chess960)
+            ),
+            None => println!("bestmove {}", eval.best_move.uci(chess960)),
+        },
+        None => println!("bestmove 0000"),
+    }
+}
+
+// the searcher lives on the search thread while one runs and comes back when it ends
+enum Engine {
+    Idle(Box<Searcher>),
+    Searching(JoinHandle<Box<Searcher>>),
+}
+
+impl Engine {
+    // wakes a finished search that is holding back its bestmove
+    fn wake(&self) {
+        if let Engine::Searching(handle) = self {
+            handle.thread().unpark();
+        }
+    }
+    fn wait(self) -> Box<Searcher> {
+        match self {
+            Engine::Idle(searcher) => searcher,
+            Engine::Searching(handle) => handle.join().expect("search thread panicked"),
+        }
+    }
+}
+
+fn new_searcher(
+    hash_mb: usize,
+    stop: &Arc<AtomicBool>,
+    pondering: &Arc<AtomicBool>,
+) -> Box<Searcher> {
+    let mut searcher = Box::new(Searcher::new());
+    searcher.tt = Arc::new(TranspositionTable::new(hash_mb));
+    searcher.stop = stop.clone();
+    searcher.pondering = pondering.clone();
+    searcher
+}
+
+/// Reads UCI commands from stdin until `quit`.
+pub fn run() {
+    let mut state = Position::new(fen::START_FEN.to_owned());
+    let mut overhead = DEFAULT_MOVE_OVERHEAD;
+    let mut hash_mb = DEFAULT_HASH_MB;
+    // a new Hash size waits for the next go or ucinewgame, a running search keeps
+    // its table
+    let mut resize_hash = false;
+    let mut threads = 1;
+    let mut chess960 = false;
+    let stop = Arc::new(AtomicBool::new(false));
+    let pondering = Arc::new(AtomicBool::new(false));
+    let mut engine = Engine::Idle(new_searcher(hash_mb, &stop, &pondering));
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first().copied() {
+            Some("uci") => {
+                println!("id name bitboard_generator");
+                println!("id author nickacide");
+                println!(
+                    "option name Move Overhead type spin default {} min 0 max 5000",
+                    DEFAULT_MOVE_OVERHEAD
+                );
+                println!(
+                    "option name Hash type spin default {} min 1 max 4096",
+                    DEFAULT_HASH_MB
+                );
+                println!(
+                    "option name Threads type spin default 1 min 1 max {}",
+                    MAX_THREADS
+                );
+                println!("option name UCI_Chess960 type check default false");
+                println!("uciok");
+            }
+            Some("isready") => println!("readyok"),
+            Some("setoption") => {
+                let name_at = tokens.iter().position(|&token| token == "name");
+                let value_at = tokens.iter().position(|&token| token == "value");
+                if let (Some(name_at), Some(value_at)) = (name_at, value_at) {
+                    let name = tokens[name_at + 1..value_at].join(" ");
+                    let text = tokens.get(value_at + 1).copied().unwrap_or("");
+                    let value = text.parse().ok();
+                    match (name.to_lowercase().as_str(), value) {
+                        ("uci_chess960", _) => chess960 = text == "true",
+                        ("move overhead", Some(value)) => overhead = value,
+                        ("threads", Some(value)) => {
+                            threads = (value as usize).clamp(1, MAX_THREADS);
+                        }
+                        ("hash", Some(value)) => {
+                            hash_mb = value as usize;
+                            resize_hash = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Some("ucinewgame") => {
+                engine.wait();
+                engine = Engine::Idle(new_searcher(hash_mb, &stop, &pondering));
+                resize_hash = false;
+            }
+            Some("position") => match parse_position(&tokens[1..], chess960) {
+                Some(position) => state = position,
+                None => eprintln!("invalid position: {}", line),
+            },
+            // Stockfish's divide, what perft-debug expects of a reference engine
+            Some("go") if tokens.get(1) == Some(&"perft") => {
+                let depth = tokens.get(2).and_then(|d| d.parse().ok()).unwrap_or(1);
+                let counts = perft::divide(&state, depth, threads, None);
+                for (piece_move, nodes) in &counts {
+                    println!("{}: {}", piece_move.uci(chess960), nodes);
+                }
+                let nodes: u64 = counts.iter().map(|(_, nodes)| nodes).sum();
+                println!("\nNodes searched: {}\n", nodes);
+            }
+            Some("go") => {
+                let mut searcher = engine.wait();
+                if resize_hash {
+                    searcher.tt = Arc::new(TranspositionTable::new(hash_mb));
+                    resize_hash = false;
+                }
+                let go = parse_go(&tokens[1..]);
+                searcher.time = match go.infinite {
+                    true => None,
+                    false => TimeManager::new(&go.clock, state.active_color, overhead),
+                };
+                searcher.node_limit = go.nodes;
+                searcher.print_info = true;
+                searcher.chess960 = chess960;
+                stop.store(false, Ordering::Relaxed);
+                pondering.store(go.ponder, Ordering::Relaxed);
+                let depth = go.depth.unwrap_or(MAX_PLY - 1);
+                let position = state.clone();
+                let (stop, pondering) = (stop.clone(), pondering.clone());
+                engine = Engine::Searching(thread::spawn(move || {
+                    let result = searcher.iterate_smp(&position, depth, threads);
+                    // bestmove may only follow stop, or ponderhit when pondering
+                    while (go.infinite || pondering.load(Ordering::Relaxed))
+                        && !stop.load(Ordering::Relaxed)
+                    {
+                        thread::park();
+                    }
+                    print_bestmove(result, chess960);
+                    searcher
+                }));
+            }
+            Some("ponderhit") => {
+                pondering.store(false, Ordering::Relaxed);
+                engine.wake();
+            }
+            Some("stop") => {
+                stop.store(true, Ordering::Relaxed);
+                engine.wake();
+                engine = Engine::Idle(engine.wait());
+            }
+            Some("quit") => {
+                stop.store(true, Ordering::Relaxed);
+                engine.wake();
+                engine.wait();
+                return;
+            }
+            _ => {}
+        }
+    }
+    stop.store(true, Ordering::Relaxed);
+    engine.wake();
+    engine.wait();
+}
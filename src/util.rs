@@ -1,14 +1,234 @@
-// pub struct BitBoard(pub u64);
-// pub struct Square(pub usize);
-
-// impl BitBoard {
-//     // fn new(b: u64) -> BitBoard {
-//     //     BitBoard(b)
-//     // }
-//     pub fn universal() -> BitBoard {
-//         BitBoard(0xffffffffffffffff)
-//     }
-//     pub fn empty() -> BitBoard {
-//         BitBoard(0)
-//     }
-// }
+use std::fmt;
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Index, IndexMut, Not, Shl, Shr,
+};
+use std::str::FromStr;
+
+use crate::Direction;
+
+/// A set of squares, bit `n` set when `Square(n)` is in it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bitboard(pub u64);
+
+/// A square of the board, a1 = 0, b1 = 1, ..., h8 = 63.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square(pub u8);
+
+const NOT_A: u64 = 0xfefefefefefefefe;
+const NOT_H: u64 = 0x7f7f7f7f7f7f7f7f;
+
+impl Bitboard {
+    /// No squares.
+    pub const EMPTY: Bitboard = Bitboard(0);
+    /// All 64 squares.
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    /// Whether the set has no squares.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    /// The number of squares in the set.
+    pub const fn popcount(self) -> u32 {
+        self.0.count_ones()
+    }
+    /// The lowest square in the set.
+    pub const fn lsb(self) -> Option<Square> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(Square(self.0.trailing_zeros() as u8))
+        }
+    }
+    /// Whether `square` is in the set.
+    pub const fn contains(self, square: Square) -> bool {
+        self.0 & 1 << square.0 != 0
+    }
+    /// Every square moved one step, squares that would wrap around the board drop
+    /// off.
+    pub const fn shift(self, direction: Direction) -> Bitboard {
+        Bitboard(match direction {
+            Direction::North => self.0 << 8,
+            Direction::NorthEast => (self.0 << 9) & NOT_A,
+            Direction::East => (self.0 << 1) & NOT_A,
+            Direction::SouthEast => (self.0 >> 7) & NOT_A,
+            Direction::South => self.0 >> 8,
+            Direction::SouthWest => (self.0 >> 9) & NOT_H,
+            Direction::West => (self.0 >> 1) & NOT_H,
+            Direction::NorthWest => (self.0 << 7) & NOT_H,
+        })
+    }
+}
+
+impl Square {
+    /// The square on `file` and `rank`, both counted from 0.
+    pub const fn new(file: u8, rank: u8) -> Square {
+        Square(rank * 8 + file)
+    }
+    /// The square's number as a table index.
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+    /// The file, 0 for the a-file.
+    pub const fn file(self) -> u8 {
+        self.0 % 8
+    }
+    /// The rank, 0 for the first rank.
+    pub const fn rank(self) -> u8 {
+        self.0 / 8
+    }
+    /// The same square seen from the other side, a1 <-> a8.
+    pub const fn flip(self) -> Square {
+        Square(self.0 ^ 56)
+    }
+    /// The set holding only this square.
+    pub const fn bitboard(self) -> Bitboard {
+        Bitboard(1 << self.0)
+    }
+}
+
+/// The squares of a [`Bitboard`], lowest first.
+pub struct Squares(u64);
+
+impl Iterator for Squares {
+    type Item = Square;
+    fn next(&mut self) -> Option<Square> {
+        let square = Bitboard(self.0).lsb()?;
+        self.0 &= self.0 - 1;
+        Some(square)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl IntoIterator for Bitboard {
+    type Item = Square;
+    type IntoIter = Squares;
+    fn into_iter(self) -> Squares {
+        Squares(self.0)
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(square: Square) -> Bitboard {
+        square.bitboard()
+    }
+}
+
+impl FromIterator<Square> for Bitboard {
+    fn from_iter<I: IntoIterator<Item = Square>>(squares: I) -> Bitboard {
+        squares
+            .into_iter()
+            .fold(Bitboard::EMPTY, |bb, square| bb | square.bitboard())
+    }
+}
+
+macro_rules! bitboard_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident) => {
+        impl $op for Bitboard {
+            type Output = Bitboard;
+            fn $method(self, other: Bitboard) -> Bitboard {
+                Bitboard(self.0.$method(other.0))
+            }
+        }
+        impl $assign for Bitboard {
+            fn $assign_method(&mut self, other: Bitboard) {
+                self.0.$assign_method(other.0)
+            }
+        }
+    };
+}
+bitboard_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+bitboard_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+bitboard_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+impl Shl<u32> for Bitboard {
+    type Output = Bitboard;
+    fn shl(self, bits: u32) -> Bitboard {
+        Bitboard(self.0 << bits)
+    }
+}
+
+impl Shr<u32> for Bitboard {
+    type Output = Bitboard;
+    fn shr(self, bits: u32) -> Bitboard {
+        Bitboard(self.0 >> bits)
+    }
+}
+
+impl fmt::Debug for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Bitboard({:#018x})", self.0)
+    }
+}
+
+// eight ranks, a8 top left
+impl fmt::Display for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for rank in (0..8).rev() {
+            let row: Vec<&str> = (0..8)
+                .map(|file| match self.contains(Square::new(file, rank)) {
+                    true => "x",
+                    false => ".",
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            char::from(b'a' + self.file()),
+            char::from(b'1' + self.rank())
+        )
+    }
+}
+
+impl fmt::Debug for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl FromStr for Square {
+    type Err = String;
+    fn from_str(text: &str) -> Result<Square, String> {
+        match text.as_bytes() {
+            &[file @ b'a'..=b'h', rank @ b'1'..=b'8'] => Ok(Square::new(file - b'a', rank - b'1')),
+            _ => Err(format!("invalid square {:?}", text)),
+        }
+    }
+}
+
+impl<T> Index<Square> for [T; 64] {
+    type Output = T;
+    fn index(&self, square: Square) -> &T {
+        &self[square.index()]
+    }
+}
+
+impl<T> IndexMut<Square> for [T; 64] {
+    fn index_mut(&mut self, square: Square) -> &mut T {
+        &mut self[square.index()]
+    }
+}
+
+impl<T> Index<Square> for Vec<T> {
+    type Output = T;
+    fn index(&self, square: Square) -> &T {
+        &self[square.index()]
+    }
+}
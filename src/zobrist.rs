@@ -0,0 +1,99 @@
+use crate::position::Pieces;
+use crate::{Color, PieceType, Position};
+
+pub struct ZobristKeys {
+    pub pieces: [[[u64; 64]; 6]; 2], // [color][piece type][square]
+    pub castling: [u64; 4],
+    pub en_passant: [u64; 8],
+    pub side: u64,
+}
+
+pub const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    (state, z ^ (z >> 31))
+}
+
+const fn generate_keys() -> ZobristKeys {
+    let mut keys = ZobristKeys {
+        pieces: [[[0; 64]; 6]; 2],
+        castling: [0; 4],
+        en_passant: [0; 8],
+        side: 0,
+    };
+    let mut seed = 0x5eed;
+    let mut key;
+    let mut color = 0;
+    while color < 2 {
+        let mut piece = 0;
+        while piece < 6 {
+            let mut square = 0;
+            while square < 64 {
+                (seed, key) = splitmix64(seed);
+                keys.pieces[color][piece][square] = key;
+                square += 1;
+            }
+            piece += 1;
+        }
+        color += 1;
+    }
+    let mut i = 0;
+    while i < 4 {
+        (seed, key) = splitmix64(seed);
+        keys.castling[i] = key;
+        i += 1;
+    }
+    i = 0;
+    while i < 8 {
+        (seed, key) = splitmix64(seed);
+        keys.en_passant[i] = key;
+        i += 1;
+    }
+    (_, keys.side) = splitmix64(seed);
+    keys
+}
+
+pub static KEYS: ZobristKeys = generate_keys();
+
+fn hash_pieces(pieces: &Pieces) -> u64 {
+    let mut key = 0;
+    for color in [Color::White, Color::Black] {
+        for (piece, bb) in pieces.bitboards(color).into_iter().enumerate() {
+            for square in bb {
+                key ^= KEYS.pieces[color as usize][piece][square];
+            }
+        }
+    }
+    key
+}
+
+// full position key, recomputed from scratch in apply_move
+pub fn hash(state: &Position) -> u64 {
+    let mut key = hash_pieces(&state.pieces);
+    let ((wq, wk), (bq, bk)) = (state.white_castling, state.black_castling);
+    for (i, right) in [wq, wk, bq, bk].into_iter().enumerate() {
+        if right {
+            key ^= KEYS.castling[i];
+        }
+    }
+    if let Some(sq) = state.en_passant {
+        key ^= KEYS.en_passant[sq.file() as usize];
+    }
+    if state.active_color == Color::Black {
+        key ^= KEYS.side;
+    }
+    key
+}
+
+// key over the pawns alone, for the pawn structure cache
+pub fn pawn_hash(pieces: &Pieces) -> u64 {
+    let mut key = 0;
+    for color in [Color::White, Color::Black] {
+        for square in pieces.bitboard(color, PieceType::Pawn) {
+            key ^= KEYS.pieces[color as usize][PieceType::Pawn as usize][square];
+        }
+    }
+    key
+}